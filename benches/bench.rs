@@ -1,5 +1,6 @@
 extern crate permu_rs;
-use permu_rs::permutation::{PermuPopulation};
+use permu_rs::permutation::{Permutation, PermuPopulation};
+use permu_rs::vj::Vj;
 use permu_rs::{Distribution, Population};
 
 #[macro_use]
@@ -11,14 +12,14 @@ use criterion::black_box;
 fn start_up(size: usize) -> (Distribution,PermuPopulation<u8>) {
     let n_samples = 1;
     let pop = PermuPopulation::<u8>::random(5,size);
-    let mut distr = pop.learn();
+    let distr = pop.learn();
     (distr,
      PermuPopulation::<u8>::zeros(n_samples,size))
 }
 
 fn sample(data: (&mut Distribution, &mut PermuPopulation<u8>)) {
-    let (mut distr, mut samples) = data; 
-    Population::sample(distr, samples);
+    let (distr, samples) = data;
+    Population::sample(distr, samples).unwrap();
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -31,5 +32,38 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("sampling, size: 100", move |b| b.iter(|| sample(black_box((&mut distr, &mut zeros)))));
 }
 
-criterion_group!(benches, criterion_benchmark);
+fn learn_benchmark(c: &mut Criterion) {
+    c.bench_function_over_inputs("learn, pop size: 100", |b, &&length| {
+        let pop = PermuPopulation::<u8>::random(100, length);
+        b.iter(|| black_box(&pop).learn())
+    }, &[50, 100, 200]);
+}
+
+#[cfg(feature = "rayon")]
+fn learn_par_benchmark(c: &mut Criterion) {
+    c.bench_function_over_inputs("learn_par, length: 50", |b, &&size| {
+        let pop = PermuPopulation::<u8>::random(size, 50);
+        b.iter(|| black_box(&pop).learn_par())
+    }, &[1000, 10000, 50000]);
+}
+
+#[cfg(not(feature = "rayon"))]
+fn learn_par_benchmark(_c: &mut Criterion) {}
+
+fn vj_benchmark(c: &mut Criterion) {
+    c.bench_function_over_inputs("vj from_permu", |b, &&length| {
+        let permu = Permutation::<u8>::random(length);
+        let mut vj = Vj::<u8>::zeros(length-1);
+        b.iter(|| Vj::from_permu(black_box(&permu), &mut vj).unwrap())
+    }, &[50, 100, 200]);
+
+    c.bench_function_over_inputs("vj to_permu", |b, &&length| {
+        let mut vj = Vj::<u8>::zeros(length-1);
+        Permutation::<u8>::random(length).to_vj(&mut vj).unwrap();
+        let mut permu = Permutation::<u8>::identity(length);
+        b.iter(|| black_box(&vj).to_permu(&mut permu).unwrap())
+    }, &[50, 100, 200]);
+}
+
+criterion_group!(benches, criterion_benchmark, learn_benchmark, learn_par_benchmark, vj_benchmark);
 criterion_main!(benches);
@@ -4,9 +4,17 @@
 //! experiment with permutations, different permutation based problems and
 //! bijective-transformations.
 //!
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{self, Debug, Display};
+use std::fs;
+
+use rand::Rng;
+
 pub mod permutation;
 pub mod vj;
 
+use permutation::{Permutation, PermuPopulation};
+
 /// Contains the methods a `Population` should have.
 pub trait Population {
     
@@ -14,11 +22,402 @@ pub trait Population {
     fn learn(&self) -> Distribution;
 
     /// Fills a given `out` population with samples sampled from a given `distr` `Distribution`. 
-    fn sample(distr: &mut Distribution, out: &mut Self) -> Result<(), &'static str>;
+    fn sample(distr: &mut Distribution, out: &mut Self) -> Result<(), &'static str> where Self: Sized;
+
+    /// Returns the diversity of the population, as the entropy of the `Distribution` learned
+    /// from it. See `Distribution::entropy`. A population of identical individuals has a
+    /// diversity of 0.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::Population;
+    /// use permu_rs::permutation::PermuPopulation;
+    ///
+    /// assert_eq!(0.0, PermuPopulation::<u8>::identity(10, 5).diversity());
+    /// assert!(PermuPopulation::<u8>::random(100, 5).diversity() > 0.5);
+    /// ```
+    fn diversity(&self) -> f64 {
+        self.learn().entropy()
+    }
+
+    /// Learns a `Distribution` from the current population and writes it to the given path.
+    /// See `Distribution::to_csv`.
+    ///
+    /// # Errors
+    /// Returns an error if the file can not be written.
+    fn save_learned(&self, path: &str) -> Result<(), &'static str> {
+        self.learn().to_csv(path)
+    }
+
+    /// Reads a `Distribution` from the given path and fills the given `out` population with
+    /// samples sampled from it. See `Distribution::from_csv` and `sample`.
+    ///
+    /// # Errors
+    /// Returns an error if the `Distribution` can not be read or if the sampling fails.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::Population;
+    /// use permu_rs::permutation::PermuPopulation;
+    ///
+    /// let name = format!("permu_rs_save_learned_example_{}.csv", std::process::id());
+    /// let path = std::env::temp_dir().join(name);
+    /// let path = path.to_str().unwrap();
+    ///
+    /// PermuPopulation::<u8>::random(10, 5).save_learned(path).unwrap();
+    /// let mut samples = PermuPopulation::<u8>::zeros(10, 5);
+    /// PermuPopulation::load_and_sample(path, &mut samples).unwrap();
+    /// samples.population.iter().for_each(|p| assert!(p.is_permu()));
+    /// std::fs::remove_file(path).unwrap();
+    /// ```
+    fn load_and_sample(path: &str, out: &mut Self) -> Result<(), &'static str> where Self: Sized {
+        let mut distr = Distribution::from_csv(path)?;
+        Self::sample(&mut distr, out)
+    }
 }
 
+/// Tag identifying the type of a `Distribution` in files.
+const DISTRIBUTION_TAG: &str = "permu";
+
 /// Probability distribution. 
 pub struct Distribution {
     pub distribution : Vec<Vec<usize>>,
     pub soften : bool,
 }
+
+impl Distribution {
+
+    /// Samples a single `Permutation` of the given length from the `Distribution`.
+    /// As in `Population::sample`, the `Distribution` is soften before sampling if it
+    /// was not already.
+    ///
+    /// # Errors
+    /// Returns an error if the given length does not match the size of the `Distribution`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::Population;
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    ///
+    /// let pop = PermuPopulation::<u8>::random(10, 5);
+    /// let mut distr = pop.learn();
+    ///
+    /// let permu: Permutation<u8> = distr.sample_one(5, &mut rand::thread_rng()).unwrap();
+    /// assert!(permu.is_permu());
+    /// assert!(distr.soften);
+    /// ```
+    pub fn sample_one<T, R: Rng>(&mut self, length: usize, rng: &mut R) -> Result<Permutation<T>, &'static str>
+    where
+        T : Copy +
+        From<u8> +
+        TryFrom<usize> +
+        TryInto<usize> +
+        Eq +
+        rand::distributions::range::SampleRange +
+        std::cmp::PartialOrd +
+        std::ops::Sub +
+        Display + // NOTE : For debugging
+        Debug, // NOTE : For debugging
+    {
+        if self.distribution.len() != length {
+            return Err("The size of the distribution does not match with the given length");
+        }
+        self.soften();
+
+        let mut permu = Permutation::<T>::identity(length);
+        PermuPopulation::sample_permu(self, &mut permu, length, rng);
+        Ok(permu)
+    }
+
+    /// Creates a `Distribution` from a matrix of probabilities, so that distributions obtained
+    /// from external models can be sampled. Each probability is multiplied by `scale` and
+    /// rounded to an integer count. The returned `Distribution` is marked as soften, so no
+    /// pseudo-counts are added before sampling.
+    ///
+    /// # Errors
+    /// Returns an error if the matrix is not square, or if any probability is negative or
+    /// not finite.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::Distribution;
+    /// use permu_rs::permutation::Permutation;
+    ///
+    /// let probs = vec![vec![0.98, 0.01, 0.01],
+    ///                  vec![0.01, 0.98, 0.01],
+    ///                  vec![0.01, 0.01, 0.98]];
+    /// let mut distr = Distribution::from_probabilities(&probs, 1000).unwrap();
+    /// assert_eq!(vec![980, 10, 10], distr.distribution[0]);
+    ///
+    /// let identity = Permutation::<u8>::identity(3);
+    /// let mut rng = rand::thread_rng();
+    /// let hits = (0..100)
+    ///     .filter(|_| distr.sample_one::<u8, _>(3, &mut rng).unwrap() == identity)
+    ///     .count();
+    /// assert!(hits > 80);
+    /// ```
+    pub fn from_probabilities(probs: &[Vec<f64>], scale: usize) -> Result<Distribution, &'static str> {
+        if probs.iter().any(|row| row.len() != probs.len()) {
+            return Err("The probability matrix must be square");
+        }
+        if probs.iter().flatten().any(|p| !p.is_finite() || *p < 0.0) {
+            return Err("Probabilities must be finite and non-negative");
+        }
+
+        let distribution = probs.iter()
+            .map(|row| row.iter().map(|p| (p * scale as f64).round() as usize).collect())
+            .collect();
+        Ok(Distribution { distribution, soften : true })
+    }
+
+    /// Creates a `Distribution` from a counts matrix built externally. The element at row `i`
+    /// and column `j` is the number of times value `j` appears at position `i`, as in
+    /// `Population::learn`. If `soften` is false, the counts are soften before sampling.
+    ///
+    /// # Errors
+    /// Returns an error if the counts matrix is not square.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::{Distribution, Population};
+    /// use permu_rs::permutation::PermuPopulation;
+    ///
+    /// let counts = vec![vec![3,0,0], vec![0,3,0], vec![0,0,3]];
+    /// let mut distr = Distribution::from_counts(counts, false).unwrap();
+    ///
+    /// let mut samples = PermuPopulation::<u8>::zeros(10, 3);
+    /// Population::sample(&mut distr, &mut samples).unwrap();
+    /// samples.population.iter().for_each(|p| assert!(p.is_permu()));
+    ///
+    /// assert!(Distribution::from_counts(vec![vec![1,2]], false).is_err());
+    /// ```
+    pub fn from_counts(counts: Vec<Vec<usize>>, soften: bool) -> Result<Distribution, &'static str> {
+        if counts.iter().any(|row| row.len() != counts.len()) {
+            return Err("The counts matrix must be square");
+        }
+        Ok(Distribution { distribution : counts, soften })
+    }
+
+    /// Writes the `Distribution` to a CSV file in the given path. The first line is a comment
+    /// recording the type of the distribution and the `soften` flag, followed by a line for
+    /// each row of the counts matrix.
+    ///
+    /// # Errors
+    /// Returns an error if the file can not be written.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::{Distribution, Population};
+    /// use permu_rs::permutation::PermuPopulation;
+    ///
+    /// let distr = PermuPopulation::<u8>::random(10, 5).learn();
+    /// let name = format!("permu_rs_to_csv_example_{}.csv", std::process::id());
+    /// let path = std::env::temp_dir().join(name);
+    /// let path = path.to_str().unwrap();
+    ///
+    /// distr.to_csv(path).unwrap();
+    /// let loaded = Distribution::from_csv(path).unwrap();
+    /// assert_eq!(distr.distribution, loaded.distribution);
+    /// assert_eq!(distr.soften, loaded.soften);
+    /// std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn to_csv(&self, path: &str) -> Result<(), &'static str> {
+        let mut csv = format!("# {} soften={}\n", DISTRIBUTION_TAG, self.soften);
+        self.distribution.iter().for_each(|row| {
+            let row: Vec<String> = row.iter().map(|x| x.to_string()).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        });
+
+        match fs::write(path, csv) {
+            Ok(_) => Ok(()),
+            Err(_) => Err("Unable to write the distribution file"),
+        }
+    }
+
+    /// Reads a `Distribution` from a CSV file written by `to_csv`.
+    ///
+    /// # Errors
+    /// Returns an error if the file can not be read, if the header has an unknown
+    /// distribution type, or if the counts matrix can not be parsed or is not square.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::Distribution;
+    ///
+    /// let name = format!("permu_rs_from_csv_example_{}.csv", std::process::id());
+    /// let path = std::env::temp_dir().join(name);
+    /// std::fs::write(&path, "# permu soften=true\n2,1\n1,2\n").unwrap();
+    /// let distr = Distribution::from_csv(path.to_str().unwrap()).unwrap();
+    /// assert_eq!(vec![vec![2,1], vec![1,2]], distr.distribution);
+    /// assert!(distr.soften);
+    ///
+    /// std::fs::write(&path, "# unknown soften=true\n2,1\n1,2\n").unwrap();
+    /// assert!(Distribution::from_csv(path.to_str().unwrap()).is_err());
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn from_csv(path: &str) -> Result<Distribution, &'static str> {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(_) => return Err("Unable to read the distribution file"),
+        };
+        let mut lines = content.lines();
+
+        // Parse the header: # <type> soften=<bool>
+        let header: Vec<&str> = match lines.next() {
+            Some(h) => h.split_whitespace().collect(),
+            None => return Err("Empty distribution file"),
+        };
+        let soften = match header.as_slice() {
+            ["#", tag, soften] if *tag == DISTRIBUTION_TAG => match *soften {
+                "soften=true" => true,
+                "soften=false" => false,
+                _ => return Err("Unable to parse the soften flag of the distribution"),
+            },
+            ["#", _, _] => return Err("Unknown distribution type"),
+            _ => return Err("Unable to parse the header of the distribution file"),
+        };
+
+        let mut distribution = Vec::new();
+        for line in lines.filter(|l| !l.trim().is_empty()) {
+            let row: Result<Vec<usize>, _> = line.split(',').map(|x| x.trim().parse()).collect();
+            match row {
+                Ok(r) => distribution.push(r),
+                Err(_) => return Err("Unable to parse the counts of the distribution"),
+            }
+        }
+
+        if distribution.iter().any(|row| row.len() != distribution.len()) {
+            return Err("The counts matrix of the distribution must be square");
+        }
+        Ok(Distribution { distribution, soften })
+    }
+
+    /// Multiplies every count of the `Distribution` by the given factor. The normalized
+    /// probabilities of the `Distribution` do not change, unless `factor` is 0 or a count
+    /// saturates. Counts that would overflow are saturated at `usize::MAX`, combine with
+    /// `clip` to keep them bounded.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::Distribution;
+    /// let mut distr = Distribution { distribution : vec![vec![3,1], vec![1,3]], soften : false };
+    /// distr.scale(10);
+    /// assert_eq!(vec![vec![30,10], vec![10,30]], distr.distribution);
+    ///
+    /// let mut distr = Distribution { distribution : vec![vec![usize::MAX/2+1]], soften : false };
+    /// distr.scale(2);
+    /// assert_eq!(usize::MAX, distr.distribution[0][0]);
+    /// ```
+    pub fn scale(&mut self, factor: usize) {
+        self.distribution.iter_mut()
+            .for_each(|row| row.iter_mut().for_each(|x| *x = x.saturating_mul(factor)));
+    }
+
+    /// Caps every count of the `Distribution` at the given maximum.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::Distribution;
+    /// let mut distr = Distribution { distribution : vec![vec![30,10], vec![10,30]], soften : false };
+    /// distr.clip(20);
+    /// assert_eq!(vec![vec![20,10], vec![10,20]], distr.distribution);
+    /// ```
+    pub fn clip(&mut self, max: usize) {
+        self.distribution.iter_mut()
+            .for_each(|row| row.iter_mut().for_each(|x| *x = std::cmp::min(*x, max)));
+    }
+
+    /// Returns the entropy of the `Distribution` normalized to `[0, 1]`, as the mean of the
+    /// normalized entropies of its rows. The normalized entropy of row `i` is
+    /// `-sum_j p_ij ln(p_ij) / ln(n)`, where `p_ij` is the count at column `j` divided by the
+    /// sum of the row and `n` is the length of the row. Rows of length 1 have an entropy of 0,
+    /// while rows with no counts have an entropy of 1. An empty `Distribution` has an entropy
+    /// of 1.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::Distribution;
+    /// let converged = Distribution { distribution : vec![vec![4,0], vec![0,4]], soften : false };
+    /// assert_eq!(0.0, converged.entropy());
+    ///
+    /// let uniform = Distribution { distribution : vec![vec![2,2], vec![2,2]], soften : false };
+    /// assert!((uniform.entropy() - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn entropy(&self) -> f64 {
+        if self.distribution.is_empty() {
+            return 1.0;
+        }
+
+        let sum: f64 = self.distribution.iter()
+            .map(|row| {
+                let total: usize = row.iter().sum();
+                if row.len() < 2 {
+                    return 0.0;
+                }
+                if total == 0 {
+                    return 1.0;
+                }
+                let sum_plnp: f64 = row.iter()
+                    .filter(|c| **c > 0)
+                    .map(|c| {
+                        let p = *c as f64 / total as f64;
+                        p * p.ln()
+                    })
+                    .sum();
+                let h = -sum_plnp / (row.len() as f64).ln();
+                // Converged rows give -0.0, that must be reported as 0
+                if h > 0.0 { h } else { 0.0 }
+            })
+            .sum();
+        sum / self.distribution.len() as f64
+    }
+
+    /// Softens the `Distribution` by adding one to every element of the matrix,
+    /// only if it is not already soften.
+    fn soften(&mut self) {
+        if !self.soften {
+            self.distribution = self.distribution.iter()
+                .map(|row| row.iter().map(|x| x+1).collect())
+                .collect();
+            self.soften = true;
+        }
+    }
+}
+
+impl fmt::Display for Distribution {
+
+    /// Formats the counts matrix of the `Distribution`, a row per line with the values aligned.
+    /// The alternate format (`{:#}`) adds a header row with the value indices and prefixes each
+    /// row with its position index.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::Distribution;
+    /// let distr = Distribution { distribution : vec![vec![10,0], vec![0,10]], soften : false };
+    /// assert_eq!("10  0\n 0 10", format!("{}", distr));
+    /// assert_eq!("  |  0  1\n0 | 10  0\n1 |  0 10", format!("{:#}", distr));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let n_cols = self.distribution.iter().map(|row| row.len()).max().unwrap_or(0);
+        let max_count = self.distribution.iter().flatten().max().copied().unwrap_or(0);
+        let width = std::cmp::max(max_count.to_string().len(),
+                                  n_cols.saturating_sub(1).to_string().len());
+        let label_width = self.distribution.len().saturating_sub(1).to_string().len();
+        let labeled = f.alternate();
+
+        let mut lines = Vec::with_capacity(self.distribution.len() + 1);
+        if labeled {
+            let header: Vec<String> = (0..n_cols).map(|j| format!("{:>w$}", j, w = width)).collect();
+            lines.push(format!("{:w$} | {}", "", header.join(" "), w = label_width));
+        }
+        self.distribution.iter().enumerate().for_each(|(i, row)| {
+            let row: Vec<String> = row.iter().map(|x| format!("{:>w$}", x, w = width)).collect();
+            match labeled {
+                true => lines.push(format!("{:>w$} | {}", i, row.join(" "), w = label_width)),
+                false => lines.push(row.join(" ")),
+            }
+        });
+        write!(f, "{}", lines.join("\n"))
+    }
+}
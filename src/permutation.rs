@@ -1,12 +1,18 @@
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut, Mul};
 
 use rand::Rng;
 
 use crate::{Population, Distribution};
 use crate::vj::{Vj, VjPopulation};
 
+/// Error returned by the samplers when the `Distribution` and the output do not fit.
+const SAMPLE_LENGTH_ERR: &str =
+    "The size of the given distribution does not match with the length of the permutations to sample";
+
 /// Contains a permutation vector and methods to generate permutations.
 #[derive(Debug)]
 #[derive(Clone)]
@@ -49,6 +55,21 @@ impl<T> Permutation<T> where
         }
     }
 
+    /// Initializes a Permutation with a copy of the given slice. See `from_vec`.
+    ///
+    /// # Errors
+    /// If the given slice is not a permutation the function will return an Error.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_slice(&[2,0,1]).unwrap();
+    /// assert_eq!(vec![2,0,1], permu.permu);
+    /// ```
+    pub fn from_slice(slice: &[T]) -> Result<Permutation<T>, &'static str> {
+        Self::from_vec(slice.to_vec())
+    }
+
     /// Initializes a Permutation with the given vector.
     /// No checking is done to the given vector, the
     /// permutation can be initialized with a vector that 
@@ -78,6 +99,28 @@ impl<T> Permutation<T> where
     /// assert_eq!(8, rand_permu.permu.len());
     /// ```
     pub fn random(length: usize) -> Permutation<T> {
+        Self::random_with_rng(length, &mut rand::thread_rng())
+    }
+
+    /// Generates a random permutation of the length given, using the given random number
+    /// generator. Useful to get reproducible results from a seeded generator.
+    ///
+    /// # Panics
+    /// If the length given is grater than the maximum value that `T` can hold,
+    /// the method will panic.
+    ///
+    /// # Example
+    /// ```
+    /// use rand::{SeedableRng, StdRng};
+    /// use permu_rs::permutation::Permutation;
+    ///
+    /// let mut rng: StdRng = SeedableRng::from_seed(&[42usize][..]);
+    /// let permu = Permutation::<u16>::random_with_rng(8, &mut rng);
+    ///
+    /// let mut rng: StdRng = SeedableRng::from_seed(&[42usize][..]);
+    /// assert_eq!(permu, Permutation::<u16>::random_with_rng(8, &mut rng));
+    /// ```
+    pub fn random_with_rng<R: Rng>(length: usize, rng: &mut R) -> Permutation<T> {
         let mut permu: Vec<T> = Vec::with_capacity(length);
         
         let zero = T::from(0u8);
@@ -89,15 +132,110 @@ impl<T> Permutation<T> where
 
         while permu.len() < length {  
             // Generate random number. n : [0, length)
-            let n = rand::thread_rng().gen_range(zero, max);
+            let n = rng.gen_range(zero, max);
 
             if !Self::contains(&permu, n) {
                 permu.push(n);
             }
         }
-        Permutation{ permu : permu }
+        Permutation{ permu }
     }
     
+    /// Generates a random permutation of the given length with exactly `descents` descents
+    /// (see `descents`), using the given random number generator. The permutation is drawn
+    /// uniformly among all the permutations with that number of descents.
+    ///
+    /// The permutation is built by inserting the values `0..length` in increasing order.
+    /// Inserting the largest value at the end or inside a descent keeps the number of descents,
+    /// while inserting it at the beginning or inside an ascent adds one. The choice at each step
+    /// is weighted with the Eulerian numbers, so that the result is uniform.
+    ///
+    /// # Errors
+    /// Returns an error if `descents` is not smaller than `length`, or if `length` does not fit
+    /// in `T`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let mut rng = rand::thread_rng();
+    /// let permu = Permutation::<u8>::random_with_descents(10, 3, &mut rng).unwrap();
+    /// assert!(permu.is_permu());
+    /// assert_eq!(3, permu.descents().len());
+    ///
+    /// assert!(Permutation::<u8>::random_with_descents(5, 5, &mut rng).is_err());
+    /// ```
+    pub fn random_with_descents<R: Rng>(length: usize, descents: usize, rng: &mut R) -> Result<Permutation<T>, &'static str> {
+        if descents >= length {
+            return Err("The number of descents must be smaller than the length");
+        }
+        if T::try_from(length).is_err() {
+            return Err("Can not create a permutation longer than the max size of the its type");
+        }
+
+        // Log-weights of the two ways of reaching k descents at length m, from length m-1:
+        // keeping the descents of a permutation with k, or adding one to a permutation with k-1.
+        // None when the way is not possible.
+        let weights = |eulerian: &Vec<Vec<f64>>, m: usize, k: usize| {
+            let keep = match k < m-1 {
+                true => Some(((k+1) as f64).ln() + eulerian[m-1][k]),
+                false => None,
+            };
+            let add = match k > 0 {
+                true => Some(((m-k) as f64).ln() + eulerian[m-1][k-1]),
+                false => None,
+            };
+            (keep, add)
+        };
+
+        // eulerian[m][k] is the logarithm of the number of permutations of length m with k
+        // descents. Logarithms are used, as the numbers overflow a f64 for long permutations.
+        let mut eulerian: Vec<Vec<f64>> = vec![vec![], vec![0.0]];
+        (2..=length).for_each(|m| {
+            let row = (0..m).map(|k| match weights(&eulerian, m, k) {
+                (Some(keep), Some(add)) => keep.max(add) + (-(keep - add).abs()).exp().ln_1p(),
+                (Some(w), None) | (None, Some(w)) => w,
+                (None, None) => unreachable!(),
+            }).collect();
+            eulerian.push(row);
+        });
+
+        // Decide, from the last insertion backwards, which insertions add a descent
+        let mut adds = vec![false; length+1];
+        let mut k = descents;
+        (2..=length).rev().for_each(|m| {
+            adds[m] = match weights(&eulerian, m, k) {
+                // Probability of adding a descent: add / (keep + add)
+                (Some(keep), Some(add)) => rng.gen::<f64>() * (1.0 + (keep - add).exp()) < 1.0,
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            if adds[m] {
+                k -= 1;
+            }
+        });
+
+        let mut permu: Vec<usize> = Vec::with_capacity(length);
+        permu.push(0);
+        (2..=length).for_each(|m| {
+            // Slots where inserting the largest value adds a descent: the beginning and the
+            // ascents. The rest of slots are the descents and the end.
+            let slots: Vec<usize> = (0..m)
+                .filter(|slot| match *slot {
+                    0 => adds[m],
+                    s if s == m-1 => !adds[m],
+                    s => (permu[s-1] < permu[s]) == adds[m],
+                })
+                .collect();
+            let slot = slots[rng.gen_range(0, slots.len())];
+            permu.insert(slot, m-1);
+        });
+
+        Ok(Permutation { permu : permu.iter().map(|e| match T::try_from(*e) {
+            Ok(v) => v,
+            Err(_) => panic!("Conversion error"),
+        }).collect() })
+    }
+
     /// Returns an identity permutation of the length given.
     ///
     /// # Panics
@@ -125,8 +263,8 @@ impl<T> Permutation<T> where
 
     /// Checks if the give `Permutation` contains an element inside.
     /// If the element is inside `Permutation` returns true.
-    fn contains(permu: &Vec<T>, item: T) -> bool {
-        permu.iter().any(|&x| x == item)
+    fn contains(permu: &[T], item: T) -> bool {
+        permu.contains(&item)
     }
     
     /// Checks if the vector inside `Permutation` is really a permutation.
@@ -177,7 +315,30 @@ impl<T> Permutation<T> where
     /// assert_eq!(ok_vj, base);
     /// ```
     pub fn to_vj(&self, out: &mut Vj<T>) -> Result<(), &'static str> {
-        Vj:: from_permu(&self, out)
+        Vj::from_permu(self, out)
+    }
+
+    /// Returns the `Vj` of the `Permutation`, allocating a `Vj` of the correct length
+    /// (the length of the `Permutation` minus one).
+    ///
+    /// # Errors
+    /// Returns an error if the `Permutation` is empty, or see `Vj::from_permu` Error section.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::vj::Vj;
+    /// use permu_rs::permutation::Permutation;
+    ///
+    /// let permu = Permutation::<u8>::from_vec(vec![3,2,1,0]).unwrap();
+    /// assert_eq!(Vj { vj : vec![3,2,1] }, permu.to_vj_owned().unwrap());
+    /// ```
+    pub fn to_vj_owned(&self) -> Result<Vj<T>, &'static str> {
+        let mut vj = match self.permu.len() {
+            0 => return Err("Can not compute the Vj of an empty permutation"),
+            n => Vj::zeros(n-1),
+        };
+        Vj::from_permu(self, &mut vj)?;
+        Ok(vj)
     }
 
     /// Returns `Result` containing a `Permutation` based on the given `Permutation`.
@@ -200,248 +361,3090 @@ impl<T> Permutation<T> where
     /// 
     /// ```
     pub fn from_vj(vj: &Vj<T>, out: &mut Permutation<T>) -> Result<(), &'static str> {
-        Vj::to_permu(&vj,out)
+        Vj::to_permu(vj, out)
     }
-}
 
-#[cfg(test)]
-mod tests_permu {
+    /// Returns the positions of one longest increasing subsequence of the `Permutation`.
+    /// The subsequence is computed in O(n log n) time by patience sorting.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,3,1,4]).unwrap();
+    /// assert_eq!(vec![1,3,4], permu.longest_increasing_subsequence());
+    /// ```
+    pub fn longest_increasing_subsequence(&self) -> Vec<usize> {
+        // tails[k] : Position of the smallest tail of an increasing subsequence of length k+1
+        let mut tails: Vec<usize> = Vec::with_capacity(self.permu.len());
+        // prev[i] : Position preceding i in the subsequence that ends at i
+        let mut prev: Vec<Option<usize>> = vec![None; self.permu.len()];
 
-    use crate::permutation::Permutation;
-    
-    #[test]
-    fn generate_rand_permus() {
-        for _i in 0..1000 {
-            let permu : Permutation<u8> = Permutation::random(40);
-            assert!(permu.is_permu());
+        self.permu.iter().enumerate().for_each(|(i, elem)| {
+            // Find the first tail that is not smaller than the current element
+            let k = tails.partition_point(|&t| self.permu[t] < *elem);
+            if k > 0 {
+                prev[i] = Some(tails[k-1]);
+            }
+            match k == tails.len() {
+                true => tails.push(i),
+                false => tails[k] = i,
+            }
+        });
+
+        // Reconstruct the subsequence backwards starting from the last tail
+        let mut lis = Vec::with_capacity(tails.len());
+        let mut current = tails.last().copied();
+        while let Some(i) = current {
+            lis.push(i);
+            current = prev[i];
         }
+        lis.reverse();
+        lis
     }
-}
-
-/// Population of `Permutations`.
-#[derive(PartialEq)]
-#[derive(Debug)]
-#[derive(Clone)]
-pub struct PermuPopulation<T> {
-    pub population : Vec<Permutation<T>>,
-    pub size : usize,
-}
 
-impl<T> PermuPopulation<T> where 
-    T : Copy +
-    From<u8> +
-    TryFrom<usize> +
-    TryInto<usize> +
-    // PartialEq<T> +
-    Eq +
-    rand::distributions::range::SampleRange +
-    std::cmp::PartialOrd +
-    std::ops::Sub +
-    Display + // NOTE : For debugging
-    Debug, // NOTE : For debugging
-{
-    /// Returns a `PermuPopulation` created from a vector of `Permutation`.
+    /// Returns a reference to the element at position `i`, or `None` if `i` is out of bounds.
     ///
     /// # Example
     /// ```
-    /// use permu_rs::permutation::{Permutation, PermuPopulation};
-    /// let vec = vec![Permutation::identity(5),
-    ///                Permutation::random(5)];
-    /// let pop = PermuPopulation::<u8>::from_vec(vec);
-    /// assert_eq!(2, pop.size);
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,1]).unwrap();
+    /// assert_eq!(Some(&0), permu.get(1));
+    /// assert_eq!(None, permu.get(3));
     /// ```
-    pub fn from_vec(vec: Vec<Permutation<T>>) -> PermuPopulation<T> {
-        let size = vec.len();
-        PermuPopulation {population : vec, size : size} 
+    pub fn get(&self, i: usize) -> Option<&T> {
+        self.permu.get(i)
     }
 
-    /// Returns a `PermuPopulation` of the size given with `Permutations` filled with zeros . 
-    /// The permutation's length must be specified. 
+    /// Sets the element at position `i` to `value`, making sure the vector
+    /// remains a permutation.
+    ///
+    /// # Errors
+    /// Returns an error if `i` is out of bounds, if `value` is not lower than the length of the
+    /// `Permutation` or if `value` is already present in another position. In any of these
+    /// cases the `Permutation` is left unchanged.
     ///
     /// # Example
     /// ```
-    /// use permu_rs::permutation::PermuPopulation;
-    /// // Creates a population of 10 permutations with length 20
-    /// let pop : PermuPopulation<u8> = PermuPopulation::zeros(10, 20);
+    /// use permu_rs::permutation::Permutation;
+    /// let mut permu = Permutation::<u8>::from_vec_unsec(vec![0,0,2]);
+    /// assert!(permu.set(1, 2).is_err()); // 2 is already in position 2
+    /// permu.set(1, 1).unwrap();
+    /// assert!(permu.is_permu());
     /// ```
-    pub fn zeros(size: usize, length: usize) -> PermuPopulation<T> {
-        let zero = T::from(0u8);
-        let zeros = vec![zero;length];
+    pub fn set(&mut self, i: usize, value: T) -> Result<(), &'static str> {
+        if i >= self.permu.len() {
+            return Err("Index out of bounds");
+        }
 
-        let mut pop : Vec<Permutation<T>> = Vec::new(); 
+        let value_usize: usize = match value.try_into() {
+            Ok(v) => v,
+            Err(_) => return Err("Error while converting T to usize"),
+        };
+        if value_usize >= self.permu.len() {
+            return Err("The value must be lower than the length of the permutation");
+        }
 
-        (0..size).for_each(|_| pop.push(Permutation::from_vec_unsec(zeros.clone())));
+        if self.permu.iter().enumerate().any(|(j, e)| j != i && *e == value) {
+            return Err("The value is already present in the permutation");
+        }
 
-        PermuPopulation {population: pop, size : size}
-    }    
-    /// Creates a `PermuPopulation` of identity `Permutation`s.
-    /// The number of `Permutation`s in the returned `PermuPopulation` is given by
-    /// `size` parameter and the length of `Permutation`s is `length`.
+        self.permu[i] = value;
+        Ok(())
+    }
+
+    /// Returns the disjoint cycles of the `Permutation`. Each cycle starts with its smallest
+    /// element, followed by the image of each element, and cycles are sorted by their first
+    /// element. Fixed points are returned as cycles of length one. The decomposition is computed
+    /// in O(n) time.
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
     ///
     /// # Example
     /// ```
-    /// use permu_rs::permutation as permu;
-    /// let population = permu::PermuPopulation::<u8>::identity(10, 5);
-    /// population.population.iter()
-    ///     .for_each(|p| assert_eq!(*p, permu::Permutation::<u8>::identity(5)));
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![1,0,3,2]).unwrap();
+    /// assert_eq!(vec![vec![0,1], vec![2,3]], permu.cycles());
+    ///
+    /// let permu = Permutation::<u8>::from_vec(vec![2,1,4,3,0]).unwrap();
+    /// assert_eq!(vec![vec![0,2,4], vec![1], vec![3]], permu.cycles());
     /// ```
-    pub fn identity(size: usize, length: usize) -> PermuPopulation<T> {
-        let mut pop : Vec<Permutation<T>> = Vec::new(); 
-        (0..size).for_each(|_| pop.push(Permutation::identity(length)));
-
-        PermuPopulation { population : pop, size : size}
-        
+    pub fn cycles(&self) -> Vec<Vec<T>> {
+        self.cycle_decomposition().iter()
+            .map(|cycle| cycle.iter().map(|e| match T::try_from(*e) {
+                Ok(v) => v,
+                Err(_) => panic!("Conversion error"),
+            }).collect())
+            .collect()
     }
-    
-    /// Initializes a `PermuPopulation` of random `Permutations` of the size and length given.
+
+    /// Returns the number of disjoint cycles of the `Permutation`, including fixed points,
+    /// without building the cycles. See `cycles`.
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
     ///
     /// # Example
     /// ```
-    /// use permu_rs::permutation::PermuPopulation;
-    /// let pop : PermuPopulation<u8> = PermuPopulation::random(10, 5);
-    /// pop.population.iter().for_each(|p| assert!(p.is_permu())); // All permutations
-    /// assert_eq!(pop.size, pop.population.len()); // PermuPopulation size check
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,1,4,3,0]).unwrap();
+    /// assert_eq!(3, permu.num_cycles());
     /// ```
-    pub fn random(size: usize, length: usize) -> PermuPopulation<T> {
-        let mut pop : Vec<Permutation<T>> = Vec::with_capacity(size);   // Initialize
-        (0..size).for_each(|_| pop.push(Permutation::random(length)) ); // Generate
-        PermuPopulation { population : pop, size : size}
+    pub fn num_cycles(&self) -> usize {
+        let mut visited = vec![false; self.permu.len()];
+        let mut cycles = 0;
+
+        (0..self.permu.len()).for_each(|start| {
+            if !visited[start] {
+                cycles += 1;
+                let mut i = start;
+                while !visited[i] {
+                    visited[i] = true;
+                    i = Self::cycle_step(&self.permu, i);
+                }
+                // In a permutation every walk ends where it started
+                if i != start {
+                    panic!("The given vector is not a permutation");
+                }
+            }
+        });
+        cycles
     }
-}
 
-impl<T> Population for PermuPopulation<T> where 
-    T : Copy +
-    From<u8> +
-    TryFrom<usize> +
-    TryInto<usize> +
-    // PartialEq<T> +
-    Eq +
-    rand::distributions::range::SampleRange +
-    std::cmp::PartialOrd +
-    std::ops::Sub +
-    Display + // NOTE : For debugging
-    Debug, // NOTE : For debugging
-{
-    
-    /// Implementation of `learn` method for `PermuPopulation`.
+    /// Returns `true` if the `Permutation` is even, this is, if it is the product of an even
+    /// number of transpositions. The parity is computed from the number of cycles, as the
+    /// `Permutation` is even if the length minus the number of cycles is even.
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
     ///
     /// # Example
     /// ```
-    /// use permu_rs::{Population, Distribution};
-    /// use permu_rs::permutation::{PermuPopulation, Permutation};
+    /// use permu_rs::permutation::Permutation;
+    /// assert!(Permutation::<u8>::identity(4).parity());
+    /// assert!(!Permutation::<u8>::from_vec(vec![1,0,2,3]).unwrap().parity());
+    /// ```
+    pub fn parity(&self) -> bool {
+        (self.permu.len() - self.num_cycles()).is_multiple_of(2)
+    }
+
+    /// Returns the sign of the `Permutation`: 1 if it is even and -1 if it is odd.
+    /// See `parity`.
     ///
-    /// let v = vec![Permutation::<u8>::from_vec_unsec(vec![0,1,2,3]),
-    ///              Permutation::<u8>::from_vec_unsec(vec![1,2,0,3])];
-    /// let pop = PermuPopulation::from_vec(v); 
-    /// let distr = pop.learn();
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
     ///
-    /// let target = vec![vec![1,1,0,0],
-    ///                   vec![0,1,1,0],
-    ///                   vec![1,0,1,0],
-    ///                   vec![0,0,0,2]];
-    /// assert_eq!(target, distr.distribution);
+    /// # Example
     /// ```
-    ///
-    // NOTE: (i : positions, j : values)
-    fn learn(&self) -> Distribution { 
-        let m = self.population[0].permu.len(); // Number of positions
-        
-        let mut distr: Vec<Vec<usize>> = vec![vec![0; m]; m]; // Init distribution matrix
+    /// use permu_rs::permutation::Permutation;
+    /// assert_eq!(1, Permutation::<u8>::from_vec(vec![1,2,0,3]).unwrap().sign());
+    /// assert_eq!(-1, Permutation::<u8>::from_vec(vec![1,0,2,3]).unwrap().sign());
+    /// ```
+    pub fn sign(&self) -> i8 {
+        match self.parity() {
+            true => 1,
+            false => -1,
+        }
+    }
 
-        (0..self.size).for_each(|i| {
-            (0..self.population[0].permu.len()).for_each(|j| {
-                let e : usize = match self.population[i].permu[j].try_into() {
-                    Ok(v) => v,
-                    Err(_) => panic!(),
-                }; 
-                distr[j][e] += 1;
+    /// Returns the cycle notation of the `Permutation` as a `String`, e.g. `(0 2 4)(1 3)`.
+    /// Each cycle starts with its smallest element and cycles are sorted by their first element.
+    /// Fixed points are only included if `fixed_points` is true. The identity permutation
+    /// without fixed points is written as `()`.
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,3,4,1,0,5]).unwrap();
+    /// assert_eq!("(0 2 4)(1 3)", permu.cycle_notation(false));
+    /// assert_eq!("(0 2 4)(1 3)(5)", permu.cycle_notation(true));
+    /// ```
+    pub fn cycle_notation(&self, fixed_points: bool) -> String {
+        let notation: String = self.cycle_decomposition().iter()
+            .filter(|cycle| fixed_points || cycle.len() > 1)
+            .map(|cycle| {
+                let elems: Vec<String> = cycle.iter().map(|e| e.to_string()).collect();
+                format!("({})", elems.join(" "))
             })
-        });
-        Distribution { distribution : distr , soften : false }
+            .collect();
+
+        match notation.is_empty() {
+            true => String::from("()"),
+            false => notation,
+        }
     }
 
-    /// Implementation of `sample` method for `PermuPopulation`.
-    ///
-    /// Example
+    /// Reverses the order of the elements of the `Permutation` in place, this is,
+    /// `permu[i]` is swapped with `permu[n-1-i]`.
     ///
+    /// # Example
     /// ```
-    /// use permu_rs::permutation::PermuPopulation;
-    /// use permu_rs::{Population, Distribution};
+    /// use permu_rs::permutation::Permutation;
+    /// let original = Permutation::<u8>::random(10);
+    /// let mut permu = original.clone();
     ///
-    /// let pop = PermuPopulation::<u8>::random(1, 5); // Population to learn from
-    /// let mut samples = PermuPopulation::<u8>::zeros(10, 5); // Population to fill with samples
-    /// let mut distr = pop.learn();
-    /// Population::sample(&mut distr, &mut samples).unwrap();
+    /// permu.reverse();
+    /// permu.reverse();
+    /// assert_eq!(original, permu);
     /// ```
-    fn sample(distr: &mut Distribution, out: &mut PermuPopulation<T>) -> Result<(), &'static str> {
-        // Check distribution and population's permus' sizes
-        let length = match distr.distribution.len() == out.population[0].permu.len() {
-            true => distr.distribution.len(),
-            false => return Err("The size of the given distribution does not match 
-                                with the length of the permutations to sample"),
-        };
-        
-        // Check if the distribution is soften 
-        if !distr.soften {
-            // If not, soften the distribution by adding one to every element of the matrix
-            distr.distribution = distr.distribution.iter()
-                .map(|row| row.iter().map(|x| x+1).collect())
-                .collect();
-            distr.soften = true;
-        }
-        
-        // let mut used_indx = Vec::<usize>::with_capacity(length);
+    pub fn reverse(&mut self) {
+        self.permu.reverse();
+    }
 
-        (0..out.size).for_each(|out_i| {
-            
-            // used_indx.clear();
-            let mut used_indx = Vec::<usize>::with_capacity(length);
-
-            // let ref_permu = Permutation::<usize>::identity(length);
-            let order = Permutation::<usize>::random(length);
-            
-            order.permu.iter().for_each(|ord| {
-
-                let (index_f, val_f) : (Vec<usize>, Vec<usize>) = distr.distribution[*ord].iter()
-                    .enumerate()
-                    .filter(|(index, _)|            // Skip the values already existing in the permutation
-                        used_indx.iter() 
-                                .find( |&x| *x == *index )
-                                .is_none())
-                    .unzip();
-
-                let max: usize = val_f.iter().sum();
-                let rand: f64 = rand::thread_rng().gen_range(0.0, max as f64);
-
-                let mut i = 0;
-                let mut s = val_f[i];
-                while (s as f64) < rand {
-                    i += 1;
-                    s += val_f[i];
-                }
-                let v = index_f[i];
-                // Never panics, as the boundaries of T are always respected here 
-                out.population[out_i].permu[*ord] = match T::try_from(v) {
-                    Ok(v) => v,
-                    Err(_) => panic!("Conversion error when sampling"),
+    /// Returns a new `Permutation` with the elements in reverse order. See `reverse`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let identity = Permutation::<u8>::identity(5);
+    /// assert_eq!(vec![4,3,2,1,0], identity.reversed().permu);
+    /// ```
+    pub fn reversed(&self) -> Permutation<T> {
+        let mut reversed = self.clone();
+        reversed.reverse();
+        reversed
+    }
+
+    /// Returns the displacement of each element of the `Permutation` from its position in the
+    /// identity, this is, `permu[i] - i` for every position `i`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let identity = Permutation::<u8>::identity(4);
+    /// assert_eq!(vec![0,0,0,0], identity.displacement());
+    ///
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,3,1]).unwrap();
+    /// assert_eq!(vec![2,-1,1,-2], permu.displacement());
+    /// ```
+    pub fn displacement(&self) -> Vec<isize> {
+        self.permu.iter().enumerate().map(|(i, e)| {
+            let e: usize = match (*e).try_into() {
+                Ok(v) => v,
+                Err(_) => panic!("Conversion error"),
+            };
+            e as isize - i as isize
+        }).collect()
+    }
+
+    /// Returns the descent set of the `Permutation`, this is, the positions `i` where
+    /// `permu[i] > permu[i+1]`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,3,1,4]).unwrap();
+    /// assert_eq!(vec![0,2], permu.descents());
+    /// ```
+    pub fn descents(&self) -> Vec<usize> {
+        self.permu.windows(2).enumerate()
+            .filter(|(_, w)| w[0] > w[1])
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the ascent set of the `Permutation`, this is, the positions `i` where
+    /// `permu[i] < permu[i+1]`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,3,1,4]).unwrap();
+    /// assert_eq!(vec![1,3], permu.ascents());
+    /// ```
+    pub fn ascents(&self) -> Vec<usize> {
+        self.permu.windows(2).enumerate()
+            .filter(|(_, w)| w[0] < w[1])
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns an iterator over all the permutations of the given length, in the order given by
+    /// the Steinhaus-Johnson-Trotter algorithm. The first permutation is the identity, and each
+    /// permutation differs from the previous one by a single swap of adjacent positions.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permus: Vec<Vec<u8>> = Permutation::<u8>::sjt_iter(3).map(|p| p.permu).collect();
+    /// assert_eq!(vec![vec![0,1,2], vec![0,2,1], vec![2,0,1],
+    ///                 vec![2,1,0], vec![1,2,0], vec![1,0,2]], permus);
+    /// ```
+    pub fn sjt_iter(length: usize) -> SjtIter<T> {
+        SjtIter {
+            permu : Some((0..length).collect()),
+            left : vec![true; length],
+            phantom : PhantomData,
+        }
+    }
+
+    /// Transforms the `Permutation` into the next one in lexicographic order. Returns `false`,
+    /// leaving the `Permutation` unchanged, if it is already the last one (the reversed
+    /// identity).
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let mut permu = Permutation::<u8>::from_vec(vec![0,2,1]).unwrap();
+    /// assert!(permu.next_permutation());
+    /// assert_eq!(vec![1,0,2], permu.permu);
+    ///
+    /// let mut last = Permutation::<u8>::from_vec(vec![2,1,0]).unwrap();
+    /// assert!(!last.next_permutation());
+    /// assert_eq!(vec![2,1,0], last.permu);
+    /// ```
+    pub fn next_permutation(&mut self) -> bool {
+        Self::step_permutation(&mut self.permu, |a, b| a < b)
+    }
+
+    /// Transforms the `Permutation` into the previous one in lexicographic order. Returns
+    /// `false`, leaving the `Permutation` unchanged, if it is already the first one (the
+    /// identity).
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let mut permu = Permutation::<u8>::from_vec(vec![1,0,2]).unwrap();
+    /// assert!(permu.prev_permutation());
+    /// assert_eq!(vec![0,2,1], permu.permu);
+    ///
+    /// let mut first = Permutation::<u8>::identity(3);
+    /// assert!(!first.prev_permutation());
+    /// ```
+    pub fn prev_permutation(&mut self) -> bool {
+        Self::step_permutation(&mut self.permu, |a, b| a > b)
+    }
+
+    /// Steps the given vector to the next permutation in the order defined by `less`: finds
+    /// the rightmost pivot smaller than its successor, swaps it with the rightmost element
+    /// of the suffix greater than it, and reverses the suffix.
+    fn step_permutation<F: Fn(&T, &T) -> bool>(permu: &mut [T], less: F) -> bool {
+        let pivot = match (1..permu.len()).rev().find(|i| less(&permu[i-1], &permu[*i])) {
+            Some(i) => i-1,
+            None => return false,
+        };
+        // Never panics, the element after the pivot is a candidate
+        let successor = (pivot+1..permu.len()).rev().find(|j| less(&permu[pivot], &permu[*j])).unwrap();
+
+        permu.swap(pivot, successor);
+        permu[pivot+1..].reverse();
+        true
+    }
+
+    /// Returns the 0-based index of the `Permutation` in the lexicographic order of all the
+    /// permutations of its length, computed with the factorial number system. Every
+    /// permutation of length up to 34 has a rank that fits in a `u128` (34! < 2^128 < 35!).
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation, or if its rank does not fit in a `u128`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// assert_eq!(0, Permutation::<u8>::identity(4).rank());
+    /// assert_eq!(3, Permutation::<u8>::from_vec(vec![1,2,0]).unwrap().rank());
+    /// assert_eq!(23, Permutation::<u8>::identity(4).reversed().rank());
+    /// ```
+    pub fn rank(&self) -> u128 {
+        if !self.is_permu() {
+            panic!("The given vector is not a permutation");
+        }
+        let permu: Vec<usize> = self.permu.iter().map(|e| match (*e).try_into() {
+            Ok(v) => v,
+            Err(_) => panic!("Conversion error"),
+        }).collect();
+        let length = permu.len();
+
+        // Horner's scheme over the factorial base digits, the digit at position i is the number
+        // of smaller elements to its right
+        permu.iter().enumerate().fold(0u128, |rank, (i, e)| {
+            let smaller = permu[i+1..].iter().filter(|x| *x < e).count() as u128;
+            match rank.checked_mul((length - i) as u128).and_then(|r| r.checked_add(smaller)) {
+                Some(r) => r,
+                None => panic!("The rank of the permutation does not fit in a u128"),
+            }
+        })
+    }
+
+    /// Returns the `Permutation` of the given length with the given lexicographic rank.
+    /// See `rank`.
+    ///
+    /// # Errors
+    /// Returns an error if the rank is not smaller than the number of permutations of the given
+    /// length, or if the length does not fit in `T`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::unrank(3, 3).unwrap();
+    /// assert_eq!(vec![1,2,0], permu.permu);
+    /// assert!(Permutation::<u8>::unrank(3, 6).is_err());
+    /// ```
+    pub fn unrank(length: usize, rank: u128) -> Result<Permutation<T>, &'static str> {
+        if T::try_from(length).is_err() {
+            return Err("Can not create a permutation longer than the max size of the its type");
+        }
+
+        // Digits of the rank in the factorial number system
+        let mut digits = vec![0usize; length];
+        let mut rest = rank;
+        (1..=length).for_each(|k| {
+            digits[length-k] = (rest % k as u128) as usize;
+            rest /= k as u128;
+        });
+        if rest != 0 {
+            return Err("The rank must be smaller than the number of permutations of the given length");
+        }
+
+        let mut remaining: Vec<usize> = (0..length).collect();
+        let permu = digits.iter().map(|d| match T::try_from(remaining.remove(*d)) {
+            Ok(v) => v,
+            Err(_) => panic!("Conversion error"),
+        }).collect();
+        Ok(Permutation { permu })
+    }
+
+    /// Returns the Kendall's tau distance between two `Permutation`s, this is, the number of
+    /// pairs of values that appear in different relative order in both permutations (the minimum
+    /// number of adjacent swaps to transform one into the other).
+    ///
+    /// The distance is computed in O(n log n) time, by mapping `self` through the inverse of
+    /// `other` and counting the inversions of the result with a Fenwick tree.
+    ///
+    /// # Errors
+    /// Returns an error if the lengths of the `Permutation`s do not match, or if any of them
+    /// is not a permutation.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let a = Permutation::<u8>::from_vec(vec![0,1,2,3]).unwrap();
+    /// let b = Permutation::<u8>::from_vec(vec![1,0,3,2]).unwrap();
+    /// assert_eq!(2, a.kendall_tau(&b).unwrap());
+    /// ```
+    pub fn kendall_tau(&self, other: &Permutation<T>) -> Result<usize, &'static str> {
+        let length = self.permu.len();
+        if length != other.permu.len() {
+            return Err("The lengths of both permutations must be equal");
+        }
+
+        // Position of each value in other
+        let mut inverse = vec![length; length];
+        for (i, e) in other.permu.iter().enumerate() {
+            match (*e).try_into() {
+                Ok(v) if v < length && inverse[v] == length => inverse[v] = i,
+                _ => return Err("The given vectors must be permutations"),
+            }
+        }
+
+        // Count the inversions of self mapped through the inverse of other, from right to left
+        let mut tree = vec![0usize; length+1]; // Fenwick tree, 1-indexed
+        let mut seen = vec![false; length];
+        let mut distance = 0;
+        for e in self.permu.iter().rev() {
+            let v = match (*e).try_into() {
+                Ok(v) if v < length && !seen[v] => v,
+                _ => return Err("The given vectors must be permutations"),
+            };
+            seen[v] = true;
+
+            // Number of already visited (right side) elements placed before in other
+            let mut i = inverse[v];
+            while i > 0 {
+                distance += tree[i];
+                i -= i & i.wrapping_neg();
+            }
+            let mut i = inverse[v] + 1;
+            while i <= length {
+                tree[i] += 1;
+                i += i & i.wrapping_neg();
+            }
+        }
+        Ok(distance)
+    }
+
+    /// Returns the Cayley distance between two `Permutation`s, this is, the minimum number of
+    /// (not necessarily adjacent) swaps to transform one into the other. The distance is the
+    /// length of the permutations minus the number of cycles of `self` composed with the
+    /// inverse of `other`.
+    ///
+    /// # Errors
+    /// Returns an error if the lengths of the `Permutation`s do not match, or if any of them
+    /// is not a permutation.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let a = Permutation::<u8>::from_vec(vec![0,1,2,3]).unwrap();
+    /// let b = Permutation::<u8>::from_vec(vec![1,2,0,3]).unwrap();
+    /// assert_eq!(2, a.cayley(&b).unwrap());
+    /// ```
+    pub fn cayley(&self, other: &Permutation<T>) -> Result<usize, &'static str> {
+        if !self.is_permu() {
+            return Err("The given vectors must be permutations");
+        }
+        let mut inverse = other.clone();
+        other.invert_into(&mut inverse)?;
+
+        let mut composed = inverse.clone();
+        self.compose(&inverse, &mut composed)?;
+        Ok(self.permu.len() - composed.num_cycles())
+    }
+
+    /// Returns the Hamming distance between two `Permutation`s, this is, the number of
+    /// positions where their values differ.
+    ///
+    /// # Errors
+    /// Returns an error if the lengths of the `Permutation`s do not match.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let a = Permutation::<u8>::from_vec(vec![0,1,2,3]).unwrap();
+    /// let b = Permutation::<u8>::from_vec(vec![1,0,2,3]).unwrap();
+    /// assert_eq!(2, a.hamming(&b).unwrap());
+    /// ```
+    pub fn hamming(&self, other: &Permutation<T>) -> Result<usize, &'static str> {
+        if self.permu.len() != other.permu.len() {
+            return Err("The lengths of the permutations must be equal");
+        }
+        Ok(self.permu.iter().zip(other.permu.iter()).filter(|(a, b)| a != b).count())
+    }
+
+    /// Returns the adjacent swaps performed by bubble sort to sort the `Permutation` into the
+    /// identity, in order. Each element `i` of the trace means swapping positions `i` and `i+1`.
+    /// The length of the trace is the number of inversions of the `Permutation`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,1]).unwrap();
+    /// assert_eq!(vec![0,1], permu.bubble_sort_trace());
+    /// ```
+    pub fn bubble_sort_trace(&self) -> Vec<usize> {
+        let mut permu = self.permu.clone();
+        let mut trace = Vec::new();
+
+        (0..permu.len()).rev().for_each(|end| {
+            (0..end).for_each(|i| {
+                if permu[i] > permu[i+1] {
+                    permu.swap(i, i+1);
+                    trace.push(i);
+                }
+            });
+        });
+        trace
+    }
+
+    /// Returns a minimum length sequence of swaps that sorts the `Permutation` into the
+    /// identity. Each pair `(i, j)` means swapping positions `i` and `j`, not necessarily
+    /// adjacent. The swaps are derived from the cycle decomposition, placing one element in its
+    /// final position with each swap, so the number of swaps is the length minus the number of
+    /// cycles (the Cayley distance to the identity).
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,1,3]).unwrap();
+    /// let swaps = permu.sort_transpositions();
+    /// assert_eq!(vec![(0,2),(0,1)], swaps);
+    ///
+    /// let mut sorted = permu.clone();
+    /// swaps.iter().for_each(|(i, j)| sorted.permu.swap(*i, *j));
+    /// assert_eq!(Permutation::identity(4), sorted);
+    /// ```
+    pub fn sort_transpositions(&self) -> Vec<(usize, usize)> {
+        let mut permu: Vec<usize> = self.permu.iter().map(|e| match (*e).try_into() {
+            Ok(v) => v,
+            Err(_) => panic!("Conversion error"),
+        }).collect();
+        let mut swaps = Vec::new();
+
+        (0..permu.len()).for_each(|i| {
+            // Send the value at position i to its place until i holds its own value
+            while permu[i] != i {
+                let j = permu[i];
+                permu.swap(i, j);
+                swaps.push((i, j));
+            }
+        });
+        swaps
+    }
+
+    /// Fills `out` with the composition of `self` and `other`, this is, `out[i] = self[other[i]]`.
+    /// The composition can also be obtained with the `*` operator, `&self * &other`.
+    ///
+    /// # Errors
+    /// Returns an error if the lengths of the three `Permutation`s do not match, or if `other`
+    /// contains a value out of range.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let a = Permutation::<u8>::from_vec(vec![1,2,0]).unwrap();
+    /// let b = Permutation::<u8>::from_vec(vec![0,2,1]).unwrap();
+    /// let mut out = Permutation::<u8>::identity(3);
+    ///
+    /// a.compose(&b, &mut out).unwrap();
+    /// assert_eq!(vec![1,0,2], out.permu);
+    /// ```
+    pub fn compose(&self, other: &Permutation<T>, out: &mut Permutation<T>) -> Result<(), &'static str> {
+        let length = self.permu.len();
+        if other.permu.len() != length || out.permu.len() != length {
+            return Err("The lengths of the permutations must be equal");
+        }
+
+        for (o, e) in out.permu.iter_mut().zip(other.permu.iter()) {
+            *o = match (*e).try_into() {
+                Ok(v) if v < length => self.permu[v],
+                _ => return Err("The given vectors must be permutations"),
+            };
+        }
+        Ok(())
+    }
+
+    /// Fills `out` with the values of `self` relabeled through `mapping`, this is,
+    /// `out[i] = mapping[self[i]]`. Unlike `compose`, which reorders the positions of `self`,
+    /// this method replaces each value of `self` by its image under `mapping`.
+    ///
+    /// # Errors
+    /// Returns an error if the lengths of the three `Permutation`s do not match, or if `self`
+    /// contains a value out of range.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![0,2,1]).unwrap();
+    /// let mapping = Permutation::<u8>::from_vec(vec![1,2,0]).unwrap();
+    /// let mut out = Permutation::<u8>::identity(3);
+    ///
+    /// permu.relabel_values(&mapping, &mut out).unwrap();
+    /// assert_eq!(vec![1,0,2], out.permu);
+    /// ```
+    pub fn relabel_values(&self, mapping: &Permutation<T>, out: &mut Permutation<T>) -> Result<(), &'static str> {
+        mapping.compose(self, out)
+    }
+
+    /// Returns the elements of `values` reordered by the `Permutation`, this is, a vector with
+    /// `values[self[i]]` at each position `i`.
+    ///
+    /// # Errors
+    /// Returns an error if the length of `values` does not match the length of the
+    /// `Permutation`, or if the `Permutation` contains a value out of range.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,1]).unwrap();
+    /// assert_eq!(vec!['c','a','b'], permu.apply(&['a','b','c']).unwrap());
+    /// ```
+    pub fn apply<U: Clone>(&self, values: &[U]) -> Result<Vec<U>, &'static str> {
+        let length = self.permu.len();
+        if values.len() != length {
+            return Err("The length of the values must match the length of the permutation");
+        }
+
+        let mut applied = Vec::with_capacity(length);
+        for e in self.permu.iter() {
+            match (*e).try_into() {
+                Ok(v) if v < length => applied.push(values[v].clone()),
+                _ => return Err("The given vector is not a permutation"),
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Composes `self` with `other` in place, this is, sets `self[i] = self[other[i]]`.
+    /// See `compose`.
+    ///
+    /// # Errors
+    /// Returns an error if the lengths of the `Permutation`s do not match, or if `other`
+    /// contains a value out of range. In that case `self` is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let mut a = Permutation::<u8>::from_vec(vec![1,2,0]).unwrap();
+    /// let b = Permutation::<u8>::from_vec(vec![0,2,1]).unwrap();
+    ///
+    /// a.compose_assign(&b).unwrap();
+    /// assert_eq!(vec![1,0,2], a.permu);
+    /// ```
+    pub fn compose_assign(&mut self, other: &Permutation<T>) -> Result<(), &'static str> {
+        let mut composed = self.clone();
+        self.compose(other, &mut composed)?;
+        self.permu = composed.permu;
+        Ok(())
+    }
+
+    /// Fills `out` with the inverse of the `Permutation`, this is, the `Permutation` `inv` such
+    /// that `inv[self[i]] = i` for every position `i`.
+    ///
+    /// # Errors
+    /// Returns an error if the length of `out` does not match the length of the `Permutation`,
+    /// or if the `Permutation` is not a permutation. In that case `out` is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,3,1]).unwrap();
+    /// let mut out = Permutation::<u8>::identity(4);
+    ///
+    /// permu.invert_into(&mut out).unwrap();
+    /// assert_eq!(vec![1,3,0,2], out.permu);
+    /// assert!(permu.invert_into(&mut Permutation::identity(3)).is_err());
+    /// ```
+    pub fn invert_into(&self, out: &mut Permutation<T>) -> Result<(), &'static str> {
+        let length = self.permu.len();
+        if out.permu.len() != length {
+            return Err("The lengths of the permutations must be equal");
+        }
+
+        let mut inverse = vec![length; length];
+        for (i, e) in self.permu.iter().enumerate() {
+            match (*e).try_into() {
+                Ok(v) if v < length && inverse[v] == length => inverse[v] = i,
+                _ => return Err("The given vector is not a permutation"),
+            }
+        }
+
+        out.permu.iter_mut().zip(inverse.iter()).for_each(|(e, v)| {
+            *e = match T::try_from(*v) {
+                Ok(v) => v,
+                Err(_) => panic!("Conversion error"),
+            };
+        });
+        Ok(())
+    }
+
+    /// Returns the inverse of the `Permutation`. See `invert_into`.
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,3,1]).unwrap();
+    /// assert_eq!(vec![1,3,0,2], permu.inverse().permu);
+    /// assert_eq!(permu, permu.inverse().inverse());
+    /// ```
+    pub fn inverse(&self) -> Permutation<T> {
+        let mut inverse = self.clone();
+        match self.invert_into(&mut inverse) {
+            Ok(_) => inverse,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Replaces the `Permutation` with its inverse. See `invert_into`.
+    ///
+    /// # Errors
+    /// Returns an error if the `Permutation` is not a permutation. In that case it is left
+    /// unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let mut permu = Permutation::<u8>::from_vec(vec![2,0,3,1]).unwrap();
+    /// permu.invert_assign().unwrap();
+    /// assert_eq!(vec![1,3,0,2], permu.permu);
+    /// ```
+    pub fn invert_assign(&mut self) -> Result<(), &'static str> {
+        let mut inverse = self.clone();
+        self.invert_into(&mut inverse)?;
+        self.permu = inverse.permu;
+        Ok(())
+    }
+
+    /// Repairs a vector that is almost a permutation, by replacing repeated values with the
+    /// missing ones. The first occurrence of each value is kept, and the missing values are
+    /// placed in increasing order. Returns the number of replaced values.
+    ///
+    /// # Errors
+    /// Returns an error if any value is out of the range of the permutation, as it can not be
+    /// repaired. In that case the `Permutation` is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let mut permu = Permutation::<u8>::from_vec_unsec(vec![3,1,1,0,3]);
+    /// assert_eq!(2, permu.repair().unwrap());
+    /// assert_eq!(vec![3,1,2,0,4], permu.permu);
+    ///
+    /// let mut permu = Permutation::<u8>::from_vec_unsec(vec![0,5,1]);
+    /// assert!(permu.repair().is_err());
+    /// ```
+    pub fn repair(&mut self) -> Result<usize, &'static str> {
+        let length = self.permu.len();
+
+        let mut values = Vec::with_capacity(length);
+        for e in self.permu.iter() {
+            match (*e).try_into() {
+                Ok(v) if v < length => values.push(v),
+                _ => return Err("The vector contains values out of range, it can not be repaired"),
+            }
+        }
+
+        let mut used = vec![false; length];
+        let repeated: Vec<usize> = (0..length).filter(|i| {
+            let is_repeated = used[values[*i]];
+            used[values[*i]] = true;
+            is_repeated
+        }).collect();
+        let missing = (0..length).filter(|v| !used[*v]);
+
+        // Each repeated value is replaced with a missing one
+        repeated.iter().zip(missing).for_each(|(i, v)| {
+            self.permu[*i] = match T::try_from(v) {
+                Ok(v) => v,
+                Err(_) => panic!("Conversion error"),
+            };
+        });
+        Ok(repeated.len())
+    }
+
+    /// Returns the one-line notation of the `Permutation`, the values separated by spaces.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,1]).unwrap();
+    /// assert_eq!("2 0 1", permu.to_one_line());
+    /// ```
+    pub fn to_one_line(&self) -> String {
+        let values: Vec<String> = self.permu.iter().map(|e| e.to_string()).collect();
+        values.join(" ")
+    }
+
+    /// Returns the two-line notation of the `Permutation`: the positions in the first line
+    /// and their values in the second one. Columns are right-aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![2,0,1]).unwrap();
+    /// assert_eq!("0 1 2\n2 0 1", permu.to_two_line());
+    /// ```
+    pub fn to_two_line(&self) -> String {
+        let (top, bottom): (Vec<String>, Vec<String>) = self.permu.iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let (i, e) = (i.to_string(), e.to_string());
+                let width = i.len().max(e.len());
+                (format!("{:>w$}", i, w = width), format!("{:>w$}", e, w = width))
+            })
+            .unzip();
+        format!("{}\n{}", top.join(" "), bottom.join(" "))
+    }
+
+    /// Returns the permutation matrix of the `Permutation` as an `ndarray::Array2`, where the
+    /// element at row `i` and column `permu[i]` is 1 and the rest are 0.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![1,2,0]).unwrap();
+    /// let matrix = permu.to_ndarray_matrix();
+    /// assert_eq!(ndarray::arr2(&[[0,1,0],
+    ///                            [0,0,1],
+    ///                            [1,0,0]]), matrix);
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn to_ndarray_matrix(&self) -> ndarray::Array2<u8> {
+        let length = self.permu.len();
+        let mut matrix = ndarray::Array2::<u8>::zeros((length, length));
+
+        self.permu.iter().enumerate().for_each(|(i, e)| {
+            let e: usize = match (*e).try_into() {
+                Ok(v) => v,
+                Err(_) => panic!("Conversion error"),
+            };
+            matrix[[i, e]] = 1;
+        });
+        matrix
+    }
+
+    /// Creates a `Permutation` from a permutation matrix given as an `ndarray::Array2`.
+    /// See `to_ndarray_matrix`.
+    ///
+    /// # Errors
+    /// Returns an error if the given matrix is not a permutation matrix, this is, if it is not
+    /// square or if any row or column does not contain exactly one 1 and 0s elsewhere.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let matrix = ndarray::arr2(&[[0,1,0],
+    ///                              [0,0,1],
+    ///                              [1,0,0]]);
+    /// let permu = Permutation::<u8>::from_ndarray_matrix(&matrix).unwrap();
+    /// assert_eq!(vec![1,2,0], permu.permu);
+    /// ```
+    #[cfg(feature = "ndarray")]
+    pub fn from_ndarray_matrix(matrix: &ndarray::Array2<u8>) -> Result<Permutation<T>, &'static str> {
+        let (rows, cols) = matrix.dim();
+        if rows != cols {
+            return Err("A permutation matrix must be square");
+        }
+
+        let mut permu: Vec<T> = Vec::with_capacity(rows);
+        for row in matrix.rows() {
+            if row.iter().any(|x| *x > 1) || row.iter().filter(|x| **x == 1).count() != 1 {
+                return Err("Every row of a permutation matrix must contain a single 1 and 0s elsewhere");
+            }
+            // Never panics, the row is known to contain a 1
+            let e = row.iter().position(|x| *x == 1).unwrap();
+            permu.push(match T::try_from(e) {
+                Ok(v) => v,
+                Err(_) => return Err("Error while converting usize to T"),
+            });
+        }
+
+        // With a single 1 in each row, a repeated column means some column has no 1
+        Permutation::from_vec(permu)
+            .map_err(|_| "Every column of a permutation matrix must contain a single 1")
+    }
+
+    /// Combines the given `Permutation`s into a block-diagonal `Permutation`, where each part
+    /// occupies its own block of consecutive positions and values. The length of the result is
+    /// the sum of the lengths of the parts.
+    ///
+    /// # Errors
+    /// Returns an error if any of the parts is not a permutation, or if the resulting
+    /// `Permutation` is too long for the type `T`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let a = Permutation::<u8>::from_vec(vec![1,0]).unwrap();
+    /// let b = Permutation::<u8>::from_vec(vec![2,0,1]).unwrap();
+    ///
+    /// let block = Permutation::block_diagonal(&[a, b]).unwrap();
+    /// assert_eq!(vec![1,0,4,2,3], block.permu);
+    /// ```
+    pub fn block_diagonal(parts: &[Permutation<T>]) -> Result<Permutation<T>, &'static str> {
+        let length = parts.iter().map(|p| p.permu.len()).sum();
+        let mut permu: Vec<T> = Vec::with_capacity(length);
+
+        for part in parts {
+            if !part.is_permu() {
+                return Err("All the parts must be permutations");
+            }
+
+            let offset = permu.len();
+            for elem in part.permu.iter() {
+                let elem: usize = match (*elem).try_into() {
+                    Ok(v) => v,
+                    Err(_) => return Err("Error while converting T to usize"),
+                };
+                permu.push(match T::try_from(elem + offset) {
+                    Ok(v) => v,
+                    Err(_) => return Err("The resulting permutation is too long for its type"),
+                });
+            }
+        }
+        Ok(Permutation { permu })
+    }
+
+    /// Returns the order of the `Permutation`, this is, the smallest number of times it must be
+    /// composed with itself to obtain the identity, computed as the least common multiple of the
+    /// lengths of its cycles. The identity has order 1.
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
+    ///
+    /// # Errors
+    /// Returns an error if the order does not fit in a `usize`. The order of permutations of
+    /// length up to 100 always fits in 64 bits, while longer permutations made of cycles of
+    /// coprime lengths can overflow it. Use `checked_order` to compute it in `u128`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let permu = Permutation::<u8>::from_vec(vec![1,0,3,4,2]).unwrap();
+    /// assert_eq!(6, permu.order().unwrap());
+    /// ```
+    pub fn order(&self) -> Result<usize, &'static str> {
+        match self.checked_order().map(usize::try_from) {
+            Some(Ok(order)) => Ok(order),
+            _ => Err("The order of the permutation does not fit in a usize"),
+        }
+    }
+
+    /// Returns the order of the `Permutation`, this is, the smallest number of times it must be
+    /// composed with itself to obtain the identity. The order is the least common multiple of
+    /// the lengths of its cycles, computed in `u128`. Returns `None` if the order does not fit in
+    /// a `u128`, which can only happen for very long permutations.
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// // Cycles of length 2 and 3
+    /// let permu = Permutation::<u8>::from_vec(vec![1,0,3,4,2]).unwrap();
+    /// assert_eq!(Some(6), permu.checked_order());
+    /// assert_eq!(Some(1), Permutation::<u8>::identity(5).checked_order());
+    /// ```
+    pub fn checked_order(&self) -> Option<u128> {
+        fn gcd(a: u128, b: u128) -> u128 {
+            match b {
+                0 => a,
+                _ => gcd(b, a % b),
+            }
+        }
+
+        let mut order: u128 = 1;
+        for cycle in self.cycle_decomposition() {
+            let len = cycle.len() as u128;
+            order = (order / gcd(order, len)).checked_mul(len)?;
+        }
+        Some(order)
+    }
+
+    /// Returns the disjoint cycles of the `Permutation` as vectors of `usize`.
+    /// Each cycle starts with its smallest element, and cycles are sorted by their first element.
+    /// Fixed points are returned as cycles of length one.
+    ///
+    /// # Panics
+    /// Panics if the `Permutation` is not a permutation.
+    fn cycle_decomposition(&self) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.permu.len()];
+        let mut cycles = Vec::new();
+
+        (0..self.permu.len()).for_each(|start| {
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                cycle.push(i);
+                i = Self::cycle_step(&self.permu, i);
+            }
+            if !cycle.is_empty() {
+                // In a permutation every walk ends where it started
+                if i != start {
+                    panic!("The given vector is not a permutation");
+                }
+                cycles.push(cycle);
+            }
+        });
+        cycles
+    }
+
+    /// Returns the value at position `i`, the next position when walking through a cycle.
+    ///
+    /// # Panics
+    /// Panics if the value is out of the range of the permutation.
+    fn cycle_step(permu: &[T], i: usize) -> usize {
+        match permu[i].try_into() {
+            Ok(v) if v < permu.len() => v,
+            _ => panic!("The given vector is not a permutation"),
+        }
+    }
+}
+
+impl<'a, T> Mul<&'a Permutation<T>> for &'a Permutation<T> where 
+    T : Copy +
+    From<u8> +
+    TryFrom<usize> +
+    TryInto<usize> +
+    // PartialEq<T> +
+    Eq +
+    rand::distributions::range::SampleRange +
+    std::cmp::PartialOrd +
+    std::ops::Sub +
+    Display + // NOTE : For debugging
+    Debug, // NOTE : For debugging
+{
+    type Output = Permutation<T>;
+
+    /// Returns the composition of both `Permutation`s, see `Permutation::compose`.
+    ///
+    /// # Panics
+    /// Panics if the composition fails, for example, if the lengths of the `Permutation`s
+    /// do not match. Use `Permutation::compose` to handle the error instead.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::Permutation;
+    /// let a = Permutation::<u8>::from_vec(vec![1,2,0]).unwrap();
+    /// let b = Permutation::<u8>::from_vec(vec![0,2,1]).unwrap();
+    /// assert_eq!(vec![1,0,2], (&a * &b).permu);
+    /// ```
+    fn mul(self, other: &'a Permutation<T>) -> Permutation<T> {
+        let mut out = self.clone();
+        match self.compose(other, &mut out) {
+            Ok(_) => out,
+            Err(e) => panic!("Unable to compose the permutations: {}", e),
+        }
+    }
+}
+
+impl<T> Mul for Permutation<T> where 
+    T : Copy +
+    From<u8> +
+    TryFrom<usize> +
+    TryInto<usize> +
+    // PartialEq<T> +
+    Eq +
+    rand::distributions::range::SampleRange +
+    std::cmp::PartialOrd +
+    std::ops::Sub +
+    Display + // NOTE : For debugging
+    Debug, // NOTE : For debugging
+{
+    type Output = Permutation<T>;
+
+    /// Returns the composition of both `Permutation`s, see the implementation for references.
+    fn mul(self, other: Permutation<T>) -> Permutation<T> {
+        &self * &other
+    }
+}
+
+/// Iterator over all the permutations of a given length following the Steinhaus-Johnson-Trotter
+/// algorithm. See `Permutation::sjt_iter`.
+pub struct SjtIter<T> {
+    permu : Option<Vec<usize>>, // Next permutation to yield, None when finished
+    left : Vec<bool>, // Direction of each value, true if it points to the left
+    phantom : PhantomData<T>,
+}
+
+impl<T> Iterator for SjtIter<T> where 
+    T : Copy +
+    From<u8> +
+    TryFrom<usize> +
+    TryInto<usize> +
+    // PartialEq<T> +
+    Eq +
+    rand::distributions::range::SampleRange +
+    std::cmp::PartialOrd +
+    std::ops::Sub +
+    Display + // NOTE : For debugging
+    Debug, // NOTE : For debugging
+{
+    type Item = Permutation<T>;
+
+    fn next(&mut self) -> Option<Permutation<T>> {
+        let current = self.permu.take()?;
+        let length = current.len();
+
+        // Find the largest mobile value: a value pointing to a smaller adjacent value
+        let mobile = (0..length)
+            .filter(|&i| {
+                let neighbor = match self.left[current[i]] {
+                    true => i.checked_sub(1),
+                    false => if i+1 < length { Some(i+1) } else { None },
+                };
+                neighbor.is_some_and(|j| current[j] < current[i])
+            })
+            .max_by_key(|&i| current[i]);
+
+        // If there is no mobile value, the current permutation is the last one
+        if let Some(i) = mobile {
+            let value = current[i];
+            let j = if self.left[value] { i-1 } else { i+1 };
+
+            let mut next = current.clone();
+            next.swap(i, j);
+            // Reverse the direction of all the values greater than the moved one
+            self.left.iter_mut().skip(value+1).for_each(|l| *l = !*l);
+            self.permu = Some(next);
+        }
+
+        let permu = current.iter().map(|e| match T::try_from(*e) {
+            Ok(v) => v,
+            Err(_) => panic!("Can not create a permutation longer than the max size of the its type"),
+        }).collect();
+        Some(Permutation { permu })
+    }
+}
+
+/// Builder of `Permutation`s, that applies a sequence of operations to an identity permutation.
+///
+/// # Example
+/// ```
+/// use permu_rs::permutation::{Permutation, PermuBuilder};
+/// let permu = PermuBuilder::<u8>::identity(5).swap(0,4).swap(1,3).build();
+/// assert_eq!(vec![4,3,2,1,0], permu.permu);
+/// ```
+pub struct PermuBuilder<T> {
+    permu : Permutation<T>,
+}
+
+impl<T> PermuBuilder<T> where 
+    T : Copy +
+    From<u8> +
+    TryFrom<usize> +
+    TryInto<usize> +
+    // PartialEq<T> +
+    Eq +
+    rand::distributions::range::SampleRange +
+    std::cmp::PartialOrd +
+    std::ops::Sub +
+    Display + // NOTE : For debugging
+    Debug, // NOTE : For debugging
+{
+    /// Starts building from the identity permutation of the given length.
+    ///
+    /// # Panics
+    /// See `Permutation::identity`.
+    pub fn identity(length: usize) -> PermuBuilder<T> {
+        PermuBuilder { permu : Permutation::identity(length) }
+    }
+
+    /// Swaps the elements in positions `i` and `j`.
+    ///
+    /// # Panics
+    /// Panics if any of the positions is out of bounds.
+    pub fn swap(mut self, i: usize, j: usize) -> PermuBuilder<T> {
+        self.permu.permu.swap(i, j);
+        self
+    }
+
+    /// Rotates the elements `k` positions to the left, so the element in position `k` becomes
+    /// the first one.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuBuilder;
+    /// let permu = PermuBuilder::<u8>::identity(4).rotate(1).build();
+    /// assert_eq!(vec![1,2,3,0], permu.permu);
+    /// ```
+    pub fn rotate(mut self, k: usize) -> PermuBuilder<T> {
+        let length = self.permu.permu.len();
+        if length > 0 {
+            self.permu.permu.rotate_left(k % length);
+        }
+        self
+    }
+
+    /// Returns the built `Permutation`.
+    pub fn build(self) -> Permutation<T> {
+        self.permu
+    }
+}
+
+/// Returns the `Permutation` of indices that sorts the given `fitness` values, in ascending or
+/// descending order. The sort is stable, so equal values keep their original order. Applying
+/// the returned `Permutation` to the `fitness` slice, or to any parallel array, with
+/// `Permutation::apply` reorders it consistently.
+///
+/// # Example
+/// ```
+/// use permu_rs::permutation::argsort;
+/// let fitness = vec![30, 10, 20];
+/// let order = argsort(&fitness, true);
+/// assert_eq!(vec![1,2,0], order.permu);
+/// assert_eq!(vec![10,20,30], order.apply(&fitness).unwrap());
+///
+/// let names = vec!["a", "b", "c"];
+/// assert_eq!(vec!["a","c","b"], argsort(&fitness, false).apply(&names).unwrap());
+/// ```
+pub fn argsort(fitness: &[usize], ascending: bool) -> Permutation<usize> {
+    let mut indices: Vec<usize> = (0..fitness.len()).collect();
+    match ascending {
+        true => indices.sort_by_key(|i| fitness[*i]),
+        false => indices.sort_by(|a, b| fitness[*b].cmp(&fitness[*a])),
+    }
+    Permutation::from_vec_unsec(indices)
+}
+
+#[cfg(test)]
+mod tests_permu {
+
+    use crate::permutation::{Permutation, PermuPopulation, PermuBuilder, argsort};
+    use crate::vj::{Vj, VjPopulation};
+    
+    #[test]
+    fn generate_rand_permus() {
+        for _i in 0..1000 {
+            let permu : Permutation<u8> = Permutation::random(40);
+            assert!(permu.is_permu());
+        }
+    }
+
+    #[test]
+    fn longest_increasing_subsequence() {
+        for _i in 0..100 {
+            let permu : Permutation<u8> = Permutation::random(30);
+            let lis = permu.longest_increasing_subsequence();
+
+            // The subsequence must be increasing in both positions and values
+            assert!(lis.windows(2).all(|w| w[0] < w[1] && permu.permu[w[0]] < permu.permu[w[1]]));
+
+            // Compare the length with the one given by the quadratic DP
+            let mut lengths = vec![1; permu.permu.len()];
+            (0..permu.permu.len()).for_each(|i| {
+                (0..i).for_each(|j| {
+                    if permu.permu[j] < permu.permu[i] && lengths[j] + 1 > lengths[i] {
+                        lengths[i] = lengths[j] + 1;
+                    }
+                });
+            });
+            assert_eq!(*lengths.iter().max().unwrap(), lis.len());
+        }
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut permu = Permutation::<u8>::from_vec(vec![1,0,2]).unwrap();
+
+        // In range
+        assert_eq!(Some(&2), permu.get(2));
+        assert!(permu.set(2, 2).is_ok());
+
+        // Out of range
+        assert_eq!(None, permu.get(3));
+        assert!(permu.set(3, 0).is_err());
+        assert!(permu.set(0, 3).is_err());
+
+        // Breaks the permutation
+        assert!(permu.set(0, 0).is_err());
+        assert_eq!(vec![1,0,2], permu.permu);
+    }
+
+    #[test]
+    fn two_line_notation() {
+        let permu = Permutation::<u8>::from_vec(vec![10,0,1,2,3,4,5,6,7,8,9]).unwrap();
+        let notation = permu.to_two_line();
+        let lines: Vec<&str> = notation.lines().collect();
+
+        assert_eq!(2, lines.len());
+        assert_eq!(lines[0].len(), lines[1].len());
+        assert_eq!(" 0 1 2 3 4 5 6 7 8 9 10", lines[0]);
+        assert_eq!("10 0 1 2 3 4 5 6 7 8  9", lines[1]);
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn ndarray_matrix_round_trip() {
+        for _i in 0..10 {
+            let permu = Permutation::<u8>::random(20);
+            let matrix = permu.to_ndarray_matrix();
+            assert_eq!(permu, Permutation::from_ndarray_matrix(&matrix).unwrap());
+        }
+
+        let repeated_column = ndarray::arr2(&[[1,0], [1,0]]);
+        assert!(Permutation::<u8>::from_ndarray_matrix(&repeated_column).is_err());
+        let not_square = ndarray::Array2::<u8>::zeros((2, 3));
+        assert!(Permutation::<u8>::from_ndarray_matrix(&not_square).is_err());
+    }
+
+    #[test]
+    fn sjt_adjacent_transpositions() {
+        let permus: Vec<Permutation<u8>> = Permutation::sjt_iter(4).collect();
+        assert_eq!(24, permus.len());
+        permus.iter().for_each(|p| assert!(p.is_permu()));
+
+        // All the permutations are different
+        let mut sorted: Vec<Vec<u8>> = permus.iter().map(|p| p.permu.clone()).collect();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(24, sorted.len());
+
+        // Consecutive permutations differ by a single adjacent transposition
+        permus.windows(2).for_each(|w| {
+            let diff: Vec<usize> = (0..4).filter(|&i| w[0].permu[i] != w[1].permu[i]).collect();
+            assert_eq!(2, diff.len());
+            assert_eq!(diff[0]+1, diff[1]);
+        });
+    }
+
+    #[test]
+    fn kendall_tau_matches_naive() {
+        for _i in 0..20 {
+            let a = Permutation::<u8>::random(100);
+            let b = Permutation::<u8>::random(100);
+
+            // Count the discordant pairs of values in quadratic time
+            let pos = |p: &Permutation<u8>, v: u8| p.permu.iter().position(|e| *e == v).unwrap();
+            let mut naive = 0;
+            (0..100u8).for_each(|u| (u+1..100).for_each(|v| {
+                if (pos(&a, u) < pos(&a, v)) != (pos(&b, u) < pos(&b, v)) {
+                    naive += 1;
+                }
+            }));
+
+            assert_eq!(naive, a.kendall_tau(&b).unwrap());
+            assert_eq!(naive, b.kendall_tau(&a).unwrap());
+        }
+        let a = Permutation::<u8>::identity(4);
+        assert!(a.kendall_tau(&Permutation::identity(5)).is_err());
+        assert!(a.kendall_tau(&Permutation::from_vec_unsec(vec![0,0,1,2])).is_err());
+    }
+
+    #[test]
+    fn cayley_distances() {
+        let identity = Permutation::<u8>::identity(6);
+        assert_eq!(0, identity.cayley(&identity).unwrap());
+
+        let swap = PermuBuilder::<u8>::identity(6).swap(1, 4).build();
+        assert_eq!(1, identity.cayley(&swap).unwrap());
+        assert_eq!(1, swap.cayley(&identity).unwrap());
+
+        let three_cycle = Permutation::<u8>::from_vec(vec![0,2,3,1,4,5]).unwrap();
+        assert_eq!(2, identity.cayley(&three_cycle).unwrap());
+        assert_eq!(2, three_cycle.cayley(&identity).unwrap());
+
+        assert!(identity.cayley(&Permutation::identity(5)).is_err());
+        assert!(Permutation::<u8>::from_vec_unsec(vec![0,0,1]).cayley(&Permutation::identity(3)).is_err());
+    }
+
+    #[test]
+    fn sort_transpositions_are_minimal() {
+        (0..20).for_each(|_| {
+            let permu = Permutation::<u8>::random(30);
+            let swaps = permu.sort_transpositions();
+
+            let mut sorted = permu.clone();
+            swaps.iter().for_each(|(i, j)| sorted.permu.swap(*i, *j));
+            assert_eq!(Permutation::identity(30), sorted);
+            assert_eq!(permu.cayley(&Permutation::identity(30)).unwrap(), swaps.len());
+        });
+        assert!(Permutation::<u8>::identity(5).sort_transpositions().is_empty());
+    }
+
+    #[test]
+    fn lexicographic_enumeration() {
+        let mut permu = Permutation::<u8>::identity(4);
+        let mut permus = vec![permu.permu.clone()];
+        while permu.next_permutation() {
+            assert!(permu.is_permu());
+            permus.push(permu.permu.clone());
+        }
+        assert_eq!(24, permus.len());
+        assert!(permus.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(Permutation::<u8>::identity(4).reversed(), permu);
+
+        // Walking back visits the same permutations in reverse order
+        let mut back = vec![permu.permu.clone()];
+        while permu.prev_permutation() {
+            back.push(permu.permu.clone());
+        }
+        back.reverse();
+        assert_eq!(permus, back);
+    }
+
+    #[test]
+    fn rank_unrank_round_trip() {
+        let mut permu = Permutation::<u8>::identity(5);
+        let mut expected = 0;
+        loop {
+            assert_eq!(expected, permu.rank());
+            assert_eq!(permu, Permutation::unrank(5, expected).unwrap());
+            expected += 1;
+            if !permu.next_permutation() {
+                break;
+            }
+        }
+        assert_eq!(120, expected);
+        assert!(Permutation::<u8>::unrank(5, 120).is_err());
+        assert!(std::panic::catch_unwind(|| Permutation::<u8>::from_vec_unsec(vec![0,0,0]).rank()).is_err());
+
+        // The largest length whose ranks all fit in a u128
+        let last = Permutation::<u8>::identity(34).reversed();
+        assert_eq!(last, Permutation::unrank(34, last.rank()).unwrap());
+        assert_eq!((1..=34u128).product::<u128>() - 1, last.rank());
+    }
+
+    #[test]
+    fn kendall_tau_identity_and_reversal() {
+        (1..50).for_each(|n| {
+            let permu = Permutation::<u8>::random(n);
+            assert_eq!(0, permu.kendall_tau(&permu).unwrap());
+            assert_eq!(n*(n-1)/2, permu.kendall_tau(&permu.reversed()).unwrap());
+        });
+    }
+
+    #[test]
+    fn bubble_sort_trace_sorts() {
+        for _i in 0..20 {
+            let permu = Permutation::<u8>::random(30);
+            let trace = permu.bubble_sort_trace();
+
+            let mut sorted = permu.clone();
+            trace.iter().for_each(|i| sorted.permu.swap(*i, i+1));
+            assert_eq!(Permutation::identity(30), sorted);
+
+            let inversions = permu.kendall_tau(&Permutation::identity(30)).unwrap();
+            assert_eq!(inversions, trace.len());
+        }
+    }
+
+    #[test]
+    fn mul_matches_compose() {
+        let a = Permutation::<u8>::random(20);
+        let b = Permutation::<u8>::random(20);
+        let mut composed = Permutation::<u8>::identity(20);
+        a.compose(&b, &mut composed).unwrap();
+
+        assert_eq!(composed, &a * &b);
+        assert_eq!(composed, a.clone() * b.clone());
+        assert_eq!(a, &a * &Permutation::identity(20));
+
+        assert!(a.compose(&Permutation::identity(10), &mut composed).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_panics_on_length_mismatch() {
+        let _ = &Permutation::<u8>::identity(3) * &Permutation::<u8>::identity(4);
+    }
+
+    #[test]
+    fn builder_sequence() {
+        let permu = PermuBuilder::<u8>::identity(6)
+            .swap(0, 1)
+            .rotate(2)
+            .swap(5, 3)
+            .rotate(7)
+            .build();
+        assert_eq!(vec![3,4,0,1,5,2], permu.permu);
+        assert!(permu.is_permu());
+    }
+
+    #[test]
+    fn cycles_cover_every_element() {
+        (0..20).for_each(|_| {
+            let permu = Permutation::<u8>::random(25);
+            let cycles = permu.cycles();
+            assert_eq!(cycles.len(), permu.num_cycles());
+
+            let mut elements: Vec<u8> = cycles.iter().flatten().cloned().collect();
+            elements.sort();
+            assert_eq!(Permutation::<u8>::identity(25).permu, elements);
+
+            // Each element is followed by its image
+            cycles.iter().for_each(|cycle| {
+                (0..cycle.len()).for_each(|i| {
+                    assert_eq!(cycle[(i+1) % cycle.len()], permu.permu[cycle[i] as usize]);
+                });
+            });
+        });
+        assert_eq!(5, Permutation::<u8>::identity(5).num_cycles());
+    }
+
+    #[test]
+    fn cycle_methods_reject_non_permutations() {
+        let repeated = Permutation::<u8>::from_vec_unsec(vec![1,1,0]);
+        let out_of_range = Permutation::<u8>::from_vec_unsec(vec![1,3,0]);
+        [repeated, out_of_range].iter().for_each(|permu| {
+            assert!(std::panic::catch_unwind(|| permu.num_cycles()).is_err());
+            assert!(std::panic::catch_unwind(|| permu.cycles()).is_err());
+            assert!(std::panic::catch_unwind(|| permu.parity()).is_err());
+            assert!(std::panic::catch_unwind(|| permu.sign()).is_err());
+            assert!(std::panic::catch_unwind(|| permu.order()).is_err());
+        });
+        let all_equal = Permutation::<u8>::from_vec_unsec(vec![1,1,1]);
+        assert!(std::panic::catch_unwind(|| all_equal.parity()).is_err());
+    }
+
+    #[test]
+    fn parity_and_sign() {
+        let identity = Permutation::<u8>::identity(4);
+        assert!(identity.parity());
+        assert_eq!(1, identity.sign());
+
+        let transposition = PermuBuilder::<u8>::identity(4).swap(0, 3).build();
+        assert!(!transposition.parity());
+        assert_eq!(-1, transposition.sign());
+
+        // [3,2,1,0] = (0 3)(1 2), two transpositions
+        assert_eq!(1, identity.reversed().sign());
+
+        // The sign is multiplicative
+        (0..20).for_each(|_| {
+            let a = Permutation::<u8>::random(9);
+            let b = Permutation::<u8>::random(9);
+            assert_eq!(a.sign() * b.sign(), (&a * &b).sign());
+        });
+    }
+
+    #[test]
+    fn block_diagonal_cycle_type() {
+        let swap = Permutation::<u8>::from_vec(vec![1,0]).unwrap();
+        let block = Permutation::block_diagonal(&[swap.clone(), swap]).unwrap();
+
+        assert!(block.is_permu());
+        let cycle_type: Vec<usize> = block.cycle_decomposition().iter().map(|c| c.len()).collect();
+        assert_eq!(vec![2,2], cycle_type);
+
+        // Too long for u8
+        let big = Permutation::<u8>::identity(200);
+        assert!(Permutation::block_diagonal(&[big.clone(), big]).is_err());
+    }
+
+    #[test]
+    fn map_population() {
+        let pop = PermuPopulation::<u8>::random(20, 10);
+
+        // Map the inverse of each permutation
+        let inverses = pop.map(|p| {
+            let mut inv = vec![0u8; p.permu.len()];
+            p.permu.iter().enumerate().for_each(|(i, e)| inv[*e as usize] = i as u8);
+            Permutation::from_vec_unsec(inv)
+        });
+
+        assert_eq!(pop.size, inverses.size);
+        inverses.population.iter().for_each(|p| assert!(p.is_permu()));
+        pop.population.iter().zip(inverses.population.iter())
+            .for_each(|(p, inv)| (0..p.permu.len())
+                .for_each(|i| assert_eq!(i as u8, inv.permu[p.permu[i] as usize])));
+
+        let mut reversed = pop.clone();
+        reversed.map_in_place(|p| p.permu.reverse());
+        reversed.population.iter().for_each(|p| assert!(p.is_permu()));
+    }
+
+    #[test]
+    fn permu_population_from_vj() {
+        let permus = PermuPopulation::<u16>::random(10, 8);
+        let mut vjs = VjPopulation::<u16>::zeros(10, 7);
+        VjPopulation::from_permus(&permus, &mut vjs).unwrap();
+
+        // Two-step path: allocate then convert
+        let mut two_step = PermuPopulation::<u16>::zeros(10, 8);
+        vjs.to_permus(&mut two_step).unwrap();
+
+        let one_step = PermuPopulation::from_vj(&vjs).unwrap();
+        assert_eq!(two_step, one_step);
+        assert_eq!(permus, one_step);
+    }
+
+    #[test]
+    fn permu_population_from_vj_size_mismatch() {
+        let mut vjs = VjPopulation::<u8>::zeros(2, 3);
+        vjs.size = 1;
+        assert!(PermuPopulation::from_vj(&vjs).is_err());
+
+        vjs.size = 3;
+        assert!(PermuPopulation::from_vj(&vjs).is_err());
+    }
+
+    #[test]
+    fn permu_population_from_vj_ragged() {
+        let vjs = VjPopulation::<u8> {
+            population : vec![Vj { vj : vec![0,0,0] }, Vj { vj : vec![0,0] }],
+            size : 2,
+        };
+        assert!(PermuPopulation::from_vj(&vjs).is_err());
+    }
+
+    #[test]
+    fn retain_derangements() {
+        let mut pop = PermuPopulation::<u8>::random(100, 5);
+        pop.retain(|p| p.permu.iter().enumerate().all(|(i, e)| *e as usize != i));
+
+        assert_eq!(pop.size, pop.population.len());
+        pop.population.iter()
+            .for_each(|p| assert!(p.permu.iter().enumerate().all(|(i, e)| *e as usize != i)));
+
+        pop.retain(|_| false);
+        assert_eq!(0, pop.size);
+    }
+
+    #[test]
+    fn in_place_composition_and_inverse() {
+        let a = Permutation::<u8>::random(12);
+        let b = Permutation::<u8>::random(12);
+
+        let mut composed = Permutation::<u8>::identity(12);
+        a.compose(&b, &mut composed).unwrap();
+        let mut in_place = a.clone();
+        in_place.compose_assign(&b).unwrap();
+        assert_eq!(composed, in_place);
+
+        let mut inverse = a.clone();
+        inverse.invert_assign().unwrap();
+        assert_eq!(Permutation::identity(12), &a * &inverse);
+        assert_eq!(Permutation::identity(12), &inverse * &a);
+
+        inverse.invert_assign().unwrap();
+        assert_eq!(a, inverse);
+
+        let mut wrong = Permutation::<u8>::from_vec_unsec(vec![0,0,1]);
+        assert!(wrong.invert_assign().is_err());
+        assert_eq!(vec![0,0,1], wrong.permu);
+    }
+
+    #[test]
+    fn relabel_values_by_identity_and_bijection() {
+        let permu = Permutation::<u8>::from_vec(vec![3,0,2,1]).unwrap();
+        let mut out = Permutation::<u8>::identity(4);
+
+        permu.relabel_values(&Permutation::identity(4), &mut out).unwrap();
+        assert_eq!(permu, out);
+
+        // Swap the labels 0 and 3
+        let mapping = Permutation::<u8>::from_vec(vec![3,1,2,0]).unwrap();
+        permu.relabel_values(&mapping, &mut out).unwrap();
+        assert_eq!(vec![0,3,2,1], out.permu);
+
+        assert!(permu.relabel_values(&Permutation::identity(3), &mut out).is_err());
+    }
+
+    #[test]
+    fn argsort_sorts_fitness() {
+        let fitness: Vec<usize> = (0..50).map(|_| rand::random::<usize>() % 20).collect();
+
+        let ascending = argsort(&fitness, true);
+        assert!(ascending.is_permu());
+        let sorted = ascending.apply(&fitness).unwrap();
+        assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+
+        let sorted = argsort(&fitness, false).apply(&fitness).unwrap();
+        assert!(sorted.windows(2).all(|w| w[0] >= w[1]));
+    }
+
+    #[test]
+    fn inverse_round_trip() {
+        let permu = Permutation::<u16>::random(40);
+        let inverse = permu.inverse();
+        assert_eq!(permu, inverse.inverse());
+        assert_eq!(Permutation::identity(40), &permu * &inverse);
+
+        let mut out = Permutation::<u16>::identity(40);
+        permu.invert_into(&mut out).unwrap();
+        assert_eq!(inverse, out);
+    }
+
+    #[test]
+    fn order_of_two_and_three_cycles() {
+        let permu = PermuBuilder::<u8>::identity(5).swap(0, 1).build();
+        let permu = &permu * &Permutation::from_vec(vec![0,1,3,4,2]).unwrap();
+        assert_eq!(6, permu.order().unwrap());
+
+        // Composing the permutation with itself `order` times gives the identity
+        let mut power = permu.clone();
+        (1..6).for_each(|_| power.compose_assign(&permu).unwrap());
+        assert_eq!(Permutation::identity(5), power);
+
+        assert_eq!(1, Permutation::<u8>::identity(5).order().unwrap());
+    }
+
+    #[test]
+    fn checked_order_beyond_u64() {
+        // A permutation made of cycles of distinct prime lengths has the product of the primes
+        // as its order
+        let cycles_of = |primes: &[usize]| {
+            let mut permu: Vec<u16> = Vec::new();
+            primes.iter().for_each(|p| {
+                let start = permu.len();
+                (0..*p).for_each(|i| permu.push((start + (i + 1) % p) as u16));
+            });
+            Permutation::from_vec(permu).unwrap()
+        };
+
+        let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+        let order = cycles_of(&primes).checked_order().unwrap();
+        assert!(order > u64::MAX as u128);
+        assert!(cycles_of(&primes).order().is_err());
+        assert_eq!(primes.iter().map(|p| *p as u128).product::<u128>(), order);
+
+        let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61,
+                      67, 71, 73, 79, 83, 89, 97, 101, 103, 107];
+        assert_eq!(None, cycles_of(&primes).checked_order());
+    }
+
+    #[test]
+    fn descents_of_identity_and_reverse() {
+        let identity = Permutation::<u8>::identity(6);
+        assert!(identity.descents().is_empty());
+        assert_eq!(vec![0,1,2,3,4], identity.ascents());
+
+        let reversed = identity.reversed();
+        assert_eq!(vec![0,1,2,3,4], reversed.descents());
+        assert!(reversed.ascents().is_empty());
+    }
+
+    #[test]
+    fn random_with_descents_count() {
+        let mut rng = rand::thread_rng();
+        (0..8).for_each(|descents| {
+            (0..20).for_each(|_| {
+                let permu = Permutation::<u8>::random_with_descents(8, descents, &mut rng).unwrap();
+                assert!(permu.is_permu());
+                assert_eq!(descents, permu.descents().len());
+            });
+        });
+
+        // The only permutations with no or all descents are the identity and its reverse
+        assert_eq!(Permutation::<u8>::identity(6),
+                   Permutation::random_with_descents(6, 0, &mut rng).unwrap());
+        assert_eq!(Permutation::<u8>::identity(6).reversed(),
+                   Permutation::random_with_descents(6, 5, &mut rng).unwrap());
+        assert!(Permutation::<u8>::random_with_descents(0, 0, &mut rng).is_err());
+    }
+
+    #[test]
+    fn random_with_descents_long_permutations() {
+        let mut rng = rand::thread_rng();
+        [180, 200, 250, 255].iter().for_each(|length| {
+            [0, 1, 2, length/2, length-2, length-1].iter().for_each(|descents| {
+                let permu = Permutation::<u8>::random_with_descents(*length, *descents, &mut rng)
+                    .unwrap();
+                assert!(permu.is_permu());
+                assert_eq!(*descents, permu.descents().len());
+            });
+        });
+    }
+
+    #[test]
+    fn from_slice_literal() {
+        let slice: &[u8] = &[3,1,0,2];
+        assert_eq!(Permutation::from_vec(slice.to_vec()).unwrap(),
+                   Permutation::from_slice(slice).unwrap());
+        assert!(Permutation::<u8>::from_slice(&[0,0,1]).is_err());
+    }
+
+    #[test]
+    fn owned_vj_matches_in_place() {
+        let permu = Permutation::<u8>::random(10);
+        let mut vj = Vj::<u8>::zeros(9);
+        permu.to_vj(&mut vj).unwrap();
+
+        assert_eq!(vj, permu.to_vj_owned().unwrap());
+        assert!(Permutation::<u8>::from_vec_unsec(vec![]).to_vj_owned().is_err());
+    }
+
+    #[test]
+    fn fill_random_keeps_shape() {
+        let mut pop = PermuPopulation::<u8>::zeros(15, 7);
+        pop.fill_random(&mut rand::thread_rng());
+
+        assert_eq!(15, pop.size);
+        assert_eq!(15, pop.population.len());
+        pop.population.iter().for_each(|p| {
+            assert_eq!(7, p.permu.len());
+            assert!(p.is_permu());
+        });
+    }
+
+    #[test]
+    fn set_eq_ignores_order() {
+        let a = Permutation::<u8>::from_vec(vec![0,1,2,3]).unwrap();
+        let b = Permutation::<u8>::from_vec(vec![3,1,0,2]).unwrap();
+        let c = Permutation::<u8>::from_vec(vec![1,0,3,2]).unwrap();
+
+        let pop = PermuPopulation::from_vec(vec![a.clone(), b.clone(), c.clone(), a.clone()]);
+        let reordered = PermuPopulation::from_vec(vec![c.clone(), a.clone(), a.clone(), b.clone()]);
+        assert!(pop.set_eq(&reordered));
+        assert!(pop != reordered);
+
+        // Same individuals, different multiplicities
+        let different = PermuPopulation::from_vec(vec![a.clone(), b.clone(), c.clone(), b.clone()]);
+        assert!(!pop.set_eq(&different));
+    }
+
+    #[test]
+    fn hamming_distances() {
+        let permu = Permutation::<u8>::random(10);
+        assert_eq!(0, permu.hamming(&permu).unwrap());
+        assert!(permu.hamming(&Permutation::identity(9)).is_err());
+
+        let pop = PermuPopulation::<u8>::random(20, 10);
+        let distances = pop.hamming_to(&permu).unwrap();
+        assert_eq!(pop.size, distances.len());
+        pop.population.iter().zip(distances.iter()).for_each(|(p, d)| {
+            assert_eq!(*d, p.permu.iter().zip(permu.permu.iter()).filter(|(a, b)| a != b).count());
+        });
+        assert!(pop.hamming_to(&Permutation::identity(9)).is_err());
+    }
+
+    #[test]
+    fn learn_normalized_uses_size() {
+        // Only the first `size` individuals are learned
+        let mut pop = PermuPopulation::<u8>::random(10, 5);
+        pop.size = 6;
+        pop.learn_normalized().iter()
+            .for_each(|row| assert!((row.iter().sum::<f64>() - 1.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn count_derangements() {
+        let pop = PermuPopulation::<u8>::from_vec(vec![
+            Permutation::from_vec(vec![1,2,0,4,3]).unwrap(),
+            Permutation::from_vec(vec![0,1,2,3,4]).unwrap(),
+            Permutation::from_vec(vec![4,3,1,0,2]).unwrap(),
+            Permutation::from_vec(vec![1,0,2,4,3]).unwrap()]);
+
+        let is_derangement = |p: &Permutation<u8>|
+            p.permu.iter().enumerate().all(|(i, e)| *e as usize != i);
+        assert_eq!(2, pop.count_matching(is_derangement));
+        assert_eq!(4, pop.count_matching(|p| p.is_permu()));
+    }
+
+    #[test]
+    fn mean_of_identical_permutations() {
+        let permu = Permutation::<u8>::random(10);
+        let pop = PermuPopulation::from_vec(vec![permu.clone(); 5]);
+        assert_eq!(permu, pop.mean_permutation().unwrap());
+
+        assert!(PermuPopulation::<u8>::from_vec(vec![]).mean_permutation().is_err());
+    }
+
+    #[test]
+    fn clone_into_reuses_buffers() {
+        let pop = PermuPopulation::<u8>::random(10, 8);
+        let mut dst = PermuPopulation::<u8>::zeros(10, 8);
+
+        let capacities: Vec<usize> = dst.population.iter().map(|p| p.permu.capacity()).collect();
+        let pointers: Vec<*const u8> = dst.population.iter().map(|p| p.permu.as_ptr()).collect();
+
+        pop.clone_into(&mut dst).unwrap();
+        assert_eq!(pop, dst);
+        assert_eq!(capacities, dst.population.iter().map(|p| p.permu.capacity()).collect::<Vec<usize>>());
+        assert_eq!(pointers, dst.population.iter().map(|p| p.permu.as_ptr()).collect::<Vec<*const u8>>());
+
+        assert!(pop.clone_into(&mut PermuPopulation::zeros(9, 8)).is_err());
+        assert!(pop.clone_into(&mut PermuPopulation::zeros(10, 7)).is_err());
+    }
+
+    #[test]
+    fn relabel_population() {
+        let original = PermuPopulation::<u8>::random(10, 8);
+        let mut pop = original.clone();
+
+        pop.relabel(&Permutation::identity(8)).unwrap();
+        assert_eq!(original, pop);
+
+        let relabel = Permutation::<u8>::random(8);
+        let mut inverse = vec![0u8; 8];
+        relabel.permu.iter().enumerate().for_each(|(i, e)| inverse[*e as usize] = i as u8);
+
+        pop.relabel(&relabel).unwrap();
+        pop.relabel(&Permutation::from_vec(inverse).unwrap()).unwrap();
+        assert_eq!(original, pop);
+
+        assert!(pop.relabel(&Permutation::identity(7)).is_err());
+    }
+
+    #[test]
+    fn positional_diversity_converged() {
+        let permu = Permutation::<u8>::random(10);
+        let pop = PermuPopulation::from_vec(vec![permu; 8]);
+        assert_eq!(vec![1; 10], pop.positional_diversity());
+    }
+
+    #[test]
+    fn swap_and_remove_individuals() {
+        let mut pop = PermuPopulation::<u8>::random(5, 6);
+        let original = pop.clone();
+
+        pop.swap_individuals(1, 3).unwrap();
+        assert_eq!(original.population[1], pop.population[3]);
+        assert_eq!(original.population[3], pop.population[1]);
+        assert!(pop.swap_individuals(0, 5).is_err());
+
+        let removed = pop.swap_remove(0).unwrap();
+        assert_eq!(original.population[0], removed);
+        assert_eq!(original.population[4], pop.population[0]);
+        assert_eq!(4, pop.size);
+        assert!(pop.swap_remove(4).is_err());
+        assert_eq!(4, pop.size);
+    }
+
+    #[test]
+    fn mutate_through_iter_mut() {
+        let mut pop = PermuPopulation::<u8>::random(10, 6);
+        let original = pop.clone();
+
+        pop.iter_mut().for_each(|p| p.permu.swap(0, 5));
+        (0..pop.size).for_each(|i| {
+            assert!(pop[i].is_permu());
+            assert_eq!(original[i].permu[0], pop[i].permu[5]);
+            assert_eq!(original[i].permu[5], pop[i].permu[0]);
+        });
+    }
+
+    #[test]
+    fn concat_populations() {
+        let a = PermuPopulation::<u8>::random(3, 6);
+        let b = PermuPopulation::<u8>::identity(2, 6);
+
+        let merged = PermuPopulation::concat(&[&a, &b]).unwrap();
+        assert_eq!(5, merged.size);
+        assert_eq!(merged.size, merged.population.len());
+        assert_eq!(&a.population[..], &merged.population[..3]);
+        assert_eq!(&b.population[..], &merged.population[3..]);
+
+        let c = PermuPopulation::<u8>::identity(2, 7);
+        assert!(PermuPopulation::concat(&[&a, &c]).is_err());
+    }
+
+    #[test]
+    fn mallows_cayley_mean_distance() {
+        let length = 10;
+        let central = Permutation::<u8>::random(length);
+
+        let mut inv_central = vec![0; length];
+        central.permu.iter().enumerate().for_each(|(i, e)| inv_central[*e as usize] = i as u8);
+
+        // Mean Cayley distance between the samples and the central permutation
+        let mean_distance = |theta: f64| {
+            let mut pop = PermuPopulation::<u8>::zeros(2000, length);
+            pop.sample_mallows_cayley(&central, theta, &mut rand::thread_rng()).unwrap();
+
+            let total: usize = pop.population.iter().map(|p| {
+                let composed = p.permu.iter().map(|e| inv_central[*e as usize]).collect();
+                length - Permutation::from_vec_unsec(composed).cycle_decomposition().len()
+            }).sum();
+            total as f64 / pop.size as f64
+        };
+
+        // Expected distance, sum of the Bernoulli probabilities of every stage
+        let expected = |theta: f64| (0..length-1).map(|i| {
+            let k = (length-1-i) as f64 * (-theta).exp();
+            k / (1.0 + k)
+        }).sum::<f64>();
+
+        let (low, high) = (mean_distance(0.5), mean_distance(2.0));
+        assert!(low > high);
+        assert!((low - expected(0.5)).abs() < 0.3);
+        assert!((high - expected(2.0)).abs() < 0.3);
+    }
+}
+
+/// Population of `Permutations`.
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct PermuPopulation<T> {
+    pub population : Vec<Permutation<T>>,
+    pub size : usize,
+}
+
+impl<T> PermuPopulation<T> where 
+    T : Copy +
+    From<u8> +
+    TryFrom<usize> +
+    TryInto<usize> +
+    // PartialEq<T> +
+    Eq +
+    rand::distributions::range::SampleRange +
+    std::cmp::PartialOrd +
+    std::ops::Sub +
+    Display + // NOTE : For debugging
+    Debug, // NOTE : For debugging
+{
+    /// Returns a `PermuPopulation` created from a vector of `Permutation`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let vec = vec![Permutation::identity(5),
+    ///                Permutation::random(5)];
+    /// let pop = PermuPopulation::<u8>::from_vec(vec);
+    /// assert_eq!(2, pop.size);
+    /// ```
+    pub fn from_vec(vec: Vec<Permutation<T>>) -> PermuPopulation<T> {
+        let size = vec.len();
+        PermuPopulation {population : vec, size} 
+    }
+
+    /// Returns a `PermuPopulation` of the size given with `Permutations` filled with zeros . 
+    /// The permutation's length must be specified. 
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// // Creates a population of 10 permutations with length 20
+    /// let pop : PermuPopulation<u8> = PermuPopulation::zeros(10, 20);
+    /// ```
+    pub fn zeros(size: usize, length: usize) -> PermuPopulation<T> {
+        let zero = T::from(0u8);
+        let zeros = vec![zero;length];
+
+        let mut pop : Vec<Permutation<T>> = Vec::new(); 
+
+        (0..size).for_each(|_| pop.push(Permutation::from_vec_unsec(zeros.clone())));
+
+        PermuPopulation {population: pop, size}
+    }    
+    /// Creates a `PermuPopulation` of identity `Permutation`s.
+    /// The number of `Permutation`s in the returned `PermuPopulation` is given by
+    /// `size` parameter and the length of `Permutation`s is `length`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation as permu;
+    /// let population = permu::PermuPopulation::<u8>::identity(10, 5);
+    /// population.population.iter()
+    ///     .for_each(|p| assert_eq!(*p, permu::Permutation::<u8>::identity(5)));
+    /// ```
+    pub fn identity(size: usize, length: usize) -> PermuPopulation<T> {
+        let mut pop : Vec<Permutation<T>> = Vec::new(); 
+        (0..size).for_each(|_| pop.push(Permutation::identity(length)));
+
+        PermuPopulation { population : pop, size}
+        
+    }
+    
+    /// Initializes a `PermuPopulation` of random `Permutations` of the size and length given.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let pop : PermuPopulation<u8> = PermuPopulation::random(10, 5);
+    /// pop.population.iter().for_each(|p| assert!(p.is_permu())); // All permutations
+    /// assert_eq!(pop.size, pop.population.len()); // PermuPopulation size check
+    /// ```
+    pub fn random(size: usize, length: usize) -> PermuPopulation<T> {
+        Self::random_with_rng(size, length, &mut rand::thread_rng())
+    }
+
+    /// Initializes a `PermuPopulation` of random `Permutations` of the size and length given,
+    /// using the given random number generator.
+    ///
+    /// # Example
+    /// ```
+    /// use rand::{SeedableRng, StdRng};
+    /// use permu_rs::permutation::PermuPopulation;
+    ///
+    /// let mut rng: StdRng = SeedableRng::from_seed(&[42usize][..]);
+    /// let pop = PermuPopulation::<u8>::random_with_rng(10, 5, &mut rng);
+    ///
+    /// let mut rng: StdRng = SeedableRng::from_seed(&[42usize][..]);
+    /// assert_eq!(pop, PermuPopulation::<u8>::random_with_rng(10, 5, &mut rng));
+    /// ```
+    pub fn random_with_rng<R: Rng>(size: usize, length: usize, rng: &mut R) -> PermuPopulation<T> {
+        let mut pop : Vec<Permutation<T>> = Vec::with_capacity(size);   // Initialize
+        (0..size).for_each(|_| pop.push(Permutation::random_with_rng(length, rng)) ); // Generate
+        PermuPopulation { population : pop, size}
+    }
+
+    /// Overwrites every individual of the population in place with a random `Permutation` of
+    /// the same length, using the given random number generator. The size of the population and
+    /// the length of the `Permutation`s do not change.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let mut pop = PermuPopulation::<u8>::zeros(10, 5);
+    /// pop.fill_random(&mut rand::thread_rng());
+    /// pop.population.iter().for_each(|p| assert!(p.is_permu()));
+    /// ```
+    pub fn fill_random<R: Rng>(&mut self, rng: &mut R) {
+        self.population.iter_mut().for_each(|permu| {
+            permu.permu.iter_mut().enumerate().for_each(|(i, e)| {
+                *e = match T::try_from(i) {
+                    Ok(v) => v,
+                    Err(_) => panic!("Conversion error"),
+                };
+            });
+            rng.shuffle(&mut permu.permu);
+        });
+    }
+
+    /// Overwrites every element of every individual of the population with 0s, like
+    /// `PermuPopulation::zeros` but without allocating.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let mut pop = PermuPopulation::<u8>::random(10, 5);
+    /// pop.fill_zeros();
+    /// assert_eq!(PermuPopulation::<u8>::zeros(10, 5), pop);
+    /// ```
+    pub fn fill_zeros(&mut self) {
+        let zero = T::from(0u8);
+        self.population.iter_mut().for_each(|permu| permu.permu.iter_mut().for_each(|e| *e = zero));
+    }
+
+    /// Applies the given function to every `Permutation` of the population in place.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let mut pop = PermuPopulation::<u8>::identity(5, 4);
+    /// pop.map_in_place(|p| p.permu.swap(0, 1));
+    /// pop.population.iter().for_each(|p| assert_eq!(vec![1,0,2,3], p.permu));
+    /// ```
+    pub fn map_in_place<F: FnMut(&mut Permutation<T>)>(&mut self, f: F) {
+        self.population.iter_mut().for_each(f);
+    }
+
+    /// Returns a new `PermuPopulation` built from the `Permutation`s returned
+    /// by applying the given function to every individual of the population.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let pop = PermuPopulation::<u8>::random(5, 4);
+    /// let identity = pop.map(|p| Permutation::identity(p.permu.len()));
+    /// assert_eq!(PermuPopulation::<u8>::identity(5, 4), identity);
+    /// ```
+    pub fn map<F: FnMut(&Permutation<T>) -> Permutation<T>>(&self, f: F) -> PermuPopulation<T> {
+        PermuPopulation::from_vec(self.population.iter().map(f).collect())
+    }
+
+    /// Returns a new `PermuPopulation` containing the individuals of all the given populations,
+    /// in the same order they are given.
+    ///
+    /// # Errors
+    /// Returns an error if the `Permutation`s of the populations do not share the same length.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let parents = PermuPopulation::<u8>::random(10, 5);
+    /// let offspring = PermuPopulation::<u8>::random(4, 5);
+    ///
+    /// let merged = PermuPopulation::concat(&[&parents, &offspring]).unwrap();
+    /// assert_eq!(14, merged.size);
+    /// ```
+    pub fn concat(pops: &[&PermuPopulation<T>]) -> Result<PermuPopulation<T>, &'static str> {
+        let mut lengths = pops.iter().flat_map(|pop| pop.population.iter().map(|p| p.permu.len()));
+        if let Some(first) = lengths.next() {
+            if lengths.any(|len| len != first) {
+                return Err("All the permutations must have the same length");
+            }
+        }
+
+        let population: Vec<Permutation<T>> = pops.iter()
+            .flat_map(|pop| pop.population.iter().cloned())
+            .collect();
+        Ok(PermuPopulation::from_vec(population))
+    }
+
+    /// Keeps only the individuals for which the given predicate returns true, updating the
+    /// size of the population.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let mut pop = PermuPopulation::<u8>::from_vec(vec![Permutation::identity(3),
+    ///                                                    Permutation::from_vec(vec![1,2,0]).unwrap()]);
+    /// pop.retain(|p| p.permu[0] != 0);
+    /// assert_eq!(1, pop.size);
+    /// ```
+    pub fn retain<F: FnMut(&Permutation<T>) -> bool>(&mut self, f: F) {
+        self.population.retain(f);
+        self.size = self.population.len();
+    }
+
+    /// Returns the mean `Permutation` of the population. The mean position of each value
+    /// across all the individuals is computed, and the values are placed in the order given by
+    /// their mean positions. Ties are broken by placing the smaller value first.
+    ///
+    /// # Errors
+    /// Returns an error if the population is empty or if its individuals are not permutations
+    /// of the same length.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let pop = PermuPopulation::<u8>::from_vec(vec![
+    ///     Permutation::from_vec(vec![0,1,2,3]).unwrap(),
+    ///     Permutation::from_vec(vec![1,0,2,3]).unwrap(),
+    ///     Permutation::from_vec(vec![1,0,3,2]).unwrap()]);
+    /// assert_eq!(vec![1,0,2,3], pop.mean_permutation().unwrap().permu);
+    /// ```
+    pub fn mean_permutation(&self) -> Result<Permutation<T>, &'static str> {
+        let length = match self.population.first() {
+            Some(p) => p.permu.len(),
+            None => return Err("Can not compute the mean of an empty population"),
+        };
+
+        // Sum of the positions of each value, the mean scaled by the population size
+        let mut positions = vec![0usize; length];
+        for permu in self.population.iter() {
+            if permu.permu.len() != length || !permu.is_permu() {
+                return Err("All the individuals must be permutations of the same length");
+            }
+            permu.permu.iter().enumerate().for_each(|(i, e)| {
+                let e: usize = match (*e).try_into() {
+                    Ok(v) => v,
+                    Err(_) => panic!("Conversion error"),
+                };
+                positions[e] += i;
+            });
+        }
+
+        let mut values: Vec<usize> = (0..length).collect();
+        values.sort_by_key(|v| (positions[*v], *v));
+
+        let permu = values.iter().map(|v| match T::try_from(*v) {
+            Ok(v) => v,
+            Err(_) => panic!("Conversion error"),
+        }).collect();
+        Ok(Permutation { permu })
+    }
+
+    /// Copies the individuals of the population into the given `dst` population, reusing its
+    /// buffers instead of allocating new ones.
+    ///
+    /// # Errors
+    /// Returns an error if both populations do not have the same size, or if the lengths of
+    /// their `Permutation`s do not match.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let pop = PermuPopulation::<u8>::random(10, 5);
+    /// let mut buffer = PermuPopulation::<u8>::zeros(10, 5);
+    ///
+    /// pop.clone_into(&mut buffer).unwrap();
+    /// assert_eq!(pop, buffer);
+    /// ```
+    pub fn clone_into(&self, dst: &mut PermuPopulation<T>) -> Result<(), &'static str> {
+        if self.size != dst.size || self.population.len() != dst.population.len() {
+            return Err("Both populations must have the same size");
+        }
+        if self.population.iter().zip(dst.population.iter())
+            .any(|(src, dst)| src.permu.len() != dst.permu.len()) {
+            return Err("The lengths of the permutations of both populations must match");
+        }
+
+        self.population.iter().zip(dst.population.iter_mut())
+            .for_each(|(src, dst)| dst.permu.copy_from_slice(&src.permu));
+        Ok(())
+    }
+
+    /// Relabels the values of every individual of the population with the given `Permutation`,
+    /// this is, each individual `p` is replaced by the composition `relabel ∘ p`, where
+    /// `(relabel ∘ p)[i] = relabel[p[i]]`.
+    ///
+    /// # Errors
+    /// Returns an error if `relabel` is not a permutation or if its length does not match the
+    /// length of the individuals.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let mut pop = PermuPopulation::<u8>::from_vec(vec![Permutation::from_vec(vec![0,2,1]).unwrap()]);
+    /// let relabel = Permutation::<u8>::from_vec(vec![1,2,0]).unwrap();
+    ///
+    /// pop.relabel(&relabel).unwrap();
+    /// assert_eq!(vec![1,0,2], pop.population[0].permu);
+    /// ```
+    pub fn relabel(&mut self, relabel: &Permutation<T>) -> Result<(), &'static str> {
+        if !relabel.is_permu() {
+            return Err("The relabeling must be a permutation");
+        }
+        if self.population.iter().any(|p| p.permu.len() != relabel.permu.len()) {
+            return Err("The length of the relabeling must match the length of the individuals");
+        }
+
+        self.population.iter_mut().for_each(|permu| {
+            permu.permu.iter_mut().for_each(|e| {
+                let index: usize = match (*e).try_into() {
+                    Ok(v) => v,
+                    Err(_) => panic!("Conversion error"),
                 };
-                used_indx.push(index_f[i]);
-            }); 
+                *e = relabel.permu[index];
+            });
+        });
+        Ok(())
+    }
+
+    /// Returns the number of individuals of the population for which the given predicate
+    /// returns `true`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let pop = PermuPopulation::<u8>::from_vec(vec![
+    ///     Permutation::from_vec(vec![0,1,2]).unwrap(),
+    ///     Permutation::from_vec(vec![2,1,0]).unwrap(),
+    ///     Permutation::from_vec(vec![0,2,1]).unwrap()]);
+    /// assert_eq!(2, pop.count_matching(|p| p.permu[0] == 0));
+    /// ```
+    pub fn count_matching<F: Fn(&Permutation<T>) -> bool>(&self, f: F) -> usize {
+        self.population.iter().filter(|p| f(p)).count()
+    }
+
+    /// Returns the Hamming distance from every individual of the population to the given
+    /// reference `Permutation`. See `Permutation::hamming`.
+    ///
+    /// # Errors
+    /// Returns an error if the length of any individual does not match the length of the
+    /// reference.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let pop = PermuPopulation::<u8>::from_vec(vec![
+    ///     Permutation::from_vec(vec![0,1,2]).unwrap(),
+    ///     Permutation::from_vec(vec![0,2,1]).unwrap(),
+    ///     Permutation::from_vec(vec![1,2,0]).unwrap()]);
+    /// assert_eq!(vec![0,2,3], pop.hamming_to(&Permutation::identity(3)).unwrap());
+    /// ```
+    pub fn hamming_to(&self, reference: &Permutation<T>) -> Result<Vec<usize>, &'static str> {
+        self.population.iter().map(|permu| permu.hamming(reference)).collect()
+    }
+
+    /// Returns `true` if both populations contain the same individuals the same number of
+    /// times, regardless of their order. Unlike `==`, the order of the individuals in the
+    /// populations is not taken into account.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let a = Permutation::<u8>::from_vec(vec![0,1,2]).unwrap();
+    /// let b = Permutation::<u8>::from_vec(vec![2,0,1]).unwrap();
+    ///
+    /// let pop = PermuPopulation::from_vec(vec![a.clone(), b.clone()]);
+    /// assert!(pop.set_eq(&PermuPopulation::from_vec(vec![b.clone(), a.clone()])));
+    /// assert!(!pop.set_eq(&PermuPopulation::from_vec(vec![a.clone(), a.clone()])));
+    /// ```
+    pub fn set_eq(&self, other: &PermuPopulation<T>) -> bool {
+        let sorted = |pop: &PermuPopulation<T>| {
+            let mut permus: Vec<Vec<usize>> = pop.population.iter()
+                .map(|p| p.permu.iter().map(|e| match (*e).try_into() {
+                    Ok(v) => v,
+                    Err(_) => panic!("Conversion error"),
+                }).collect())
+                .collect();
+            permus.sort();
+            permus
+        };
+        self.population.len() == other.population.len() && sorted(self) == sorted(other)
+    }
+
+    /// Returns, for each position, the number of distinct values that the individuals of the
+    /// population have in that position. A position with a diversity of 1 has converged.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let pop = PermuPopulation::<u8>::from_vec(vec![
+    ///     Permutation::from_vec(vec![0,1,2,3]).unwrap(),
+    ///     Permutation::from_vec(vec![0,2,1,3]).unwrap(),
+    ///     Permutation::from_vec(vec![0,3,1,2]).unwrap()]);
+    /// assert_eq!(vec![1,3,2,2], pop.positional_diversity());
+    /// ```
+    pub fn positional_diversity(&self) -> Vec<usize> {
+        let length = self.population.first().map_or(0, |p| p.permu.len());
+        let mut values: Vec<T> = Vec::with_capacity(self.population.len());
+
+        (0..length).map(|i| {
+            values.clear();
+            self.population.iter().for_each(|p| {
+                if !values.contains(&p.permu[i]) {
+                    values.push(p.permu[i]);
+                }
+            });
+            values.len()
+        }).collect()
+    }
+
+    /// Returns the population as a matrix, where each row is the vector of an individual.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let pop = PermuPopulation::<u8>::identity(2, 3);
+    /// assert_eq!(vec![vec![0,1,2], vec![0,1,2]], pop.as_matrix());
+    /// ```
+    pub fn as_matrix(&self) -> Vec<Vec<T>> {
+        self.population.iter().map(|p| p.permu.clone()).collect()
+    }
+
+    /// Creates a `PermuPopulation` from a matrix, where each row is the vector of an individual.
+    ///
+    /// # Errors
+    /// Returns an error if any row is not a permutation or if the rows do not share the same
+    /// length.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let pop = PermuPopulation::<u8>::random(10, 5);
+    /// assert_eq!(pop, PermuPopulation::from_matrix(&pop.as_matrix()).unwrap());
+    ///
+    /// assert!(PermuPopulation::<u8>::from_matrix(&[vec![0,1], vec![0,1,2]]).is_err());
+    /// assert!(PermuPopulation::<u8>::from_matrix(&[vec![0,0]]).is_err());
+    /// ```
+    pub fn from_matrix(m: &[Vec<T>]) -> Result<PermuPopulation<T>, &'static str> {
+        if let Some(first) = m.first() {
+            if m.iter().any(|row| row.len() != first.len()) {
+                return Err("All the rows of the matrix must have the same length");
+            }
+        }
+
+        let mut population = Vec::with_capacity(m.len());
+        for row in m {
+            population.push(Permutation::from_vec(row.clone())?);
+        }
+        Ok(PermuPopulation::from_vec(population))
+    }
+
+    /// Swaps the individuals at positions `i` and `j` of the population.
+    ///
+    /// # Errors
+    /// Returns an error if any of the indices is out of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let mut pop = PermuPopulation::<u8>::from_vec(vec![Permutation::identity(3),
+    ///                                                    Permutation::from_vec(vec![2,1,0]).unwrap()]);
+    /// pop.swap_individuals(0, 1).unwrap();
+    /// assert_eq!(vec![2,1,0], pop.population[0].permu);
+    /// ```
+    pub fn swap_individuals(&mut self, i: usize, j: usize) -> Result<(), &'static str> {
+        if i >= self.population.len() || j >= self.population.len() {
+            return Err("Index out of bounds");
+        }
+        self.population.swap(i, j);
+        Ok(())
+    }
+
+    /// Removes the individual at position `i` and returns it, replacing it with the last
+    /// individual of the population. The size of the population is updated.
+    ///
+    /// # Errors
+    /// Returns an error if the index is out of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let mut pop = PermuPopulation::<u8>::identity(3, 4);
+    /// assert_eq!(Permutation::identity(4), pop.swap_remove(0).unwrap());
+    /// assert_eq!(2, pop.size);
+    /// ```
+    pub fn swap_remove(&mut self, i: usize) -> Result<Permutation<T>, &'static str> {
+        if i >= self.population.len() {
+            return Err("Index out of bounds");
+        }
+        let removed = self.population.swap_remove(i);
+        self.size = self.population.len();
+        Ok(removed)
+    }
+
+    /// Returns the distribution learned from the population normalized to probabilities, this
+    /// is, the element at row `i` and column `j` is the fraction of individuals with value `j`
+    /// at position `i`. See `Population::learn`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let pop = PermuPopulation::<u8>::random(10, 5);
+    /// pop.learn_normalized().iter()
+    ///     .for_each(|row| assert!((row.iter().sum::<f64>() - 1.0).abs() < 1e-9));
+    /// ```
+    pub fn learn_normalized(&self) -> Vec<Vec<f64>> {
+        let size = self.size as f64;
+        self.learn().distribution.iter()
+            .map(|row| row.iter().map(|x| *x as f64 / size).collect())
+            .collect()
+    }
+
+    /// Parallel version of `Population::learn`. The population is split in chunks, a partial
+    /// counts matrix is learned from each chunk in parallel, and the partial matrices are added
+    /// up. The returned `Distribution` is identical to the one returned by `learn`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// use permu_rs::Population;
+    /// let pop = PermuPopulation::<u8>::random(1000, 10);
+    /// assert_eq!(pop.learn().distribution, pop.learn_par().distribution);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn learn_par(&self) -> Distribution where T: Send + Sync {
+        use rayon::prelude::*;
+
+        let m = self.population[0].permu.len(); // Number of positions
+        let chunk_size = std::cmp::max(1, self.size / rayon::current_num_threads());
+
+        let distr = self.population[..self.size].par_chunks(chunk_size)
+            .map(|chunk| {
+                let mut partial: Vec<Vec<usize>> = vec![vec![0; m]; m];
+                chunk.iter().for_each(|permu| {
+                    permu.permu.iter().enumerate().for_each(|(j, e)| {
+                        let e: usize = match (*e).try_into() {
+                            Ok(v) => v,
+                            Err(_) => panic!("Conversion error"),
+                        };
+                        partial[j][e] += 1;
+                    });
+                });
+                partial
+            })
+            .reduce(|| vec![vec![0; m]; m], |mut a, b| {
+                a.iter_mut().zip(b.iter()).for_each(|(row_a, row_b)| {
+                    row_a.iter_mut().zip(row_b.iter()).for_each(|(x, y)| *x += y);
+                });
+                a
+            });
+        Distribution { distribution : distr, soften : false }
+    }
+
+    /// Returns an iterator over the individuals of the population.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let pop = PermuPopulation::<u8>::random(10, 5);
+    /// assert!(pop.iter().all(|p| p.is_permu()));
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, Permutation<T>> {
+        self.population.iter()
+    }
+
+    /// Returns an iterator that allows modifying each individual of the population.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// let mut pop = PermuPopulation::<u8>::identity(10, 5);
+    /// pop.iter_mut().for_each(|p| p.reverse());
+    /// assert!(pop.iter().all(|p| p.permu == vec![4,3,2,1,0]));
+    /// ```
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Permutation<T>> {
+        self.population.iter_mut()
+    }
+
+    /// Returns a `PermuPopulation` with the `Permutation` representation of every `Vj` in the
+    /// given `VjPopulation`. The output population is sized internally, so there is no need to
+    /// allocate it beforehand as with `VjPopulation::to_permus`.
+    ///
+    /// # Errors
+    /// Returns an error if the `size` of the `VjPopulation` does not match the number of `Vj`s
+    /// it contains, if the `Vj`s do not all have the same length, or if any of them can not be
+    /// converted to a `Permutation` (see `Vj::to_permu` Errors section).
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// use permu_rs::vj::VjPopulation;
+    ///
+    /// let vjs = VjPopulation::<u8>::zeros(5, 3);
+    /// let permus = PermuPopulation::from_vj(&vjs).unwrap();
+    /// assert_eq!(PermuPopulation::<u8>::identity(5, 4), permus);
+    /// ```
+    pub fn from_vj(vjs: &VjPopulation<T>) -> Result<PermuPopulation<T>, &'static str> {
+        if vjs.size != vjs.population.len() {
+            return Err("The size of the VjPopulation must match the number of Vjs it contains");
+        }
+        if vjs.population.is_empty() {
+            return Ok(PermuPopulation::from_vec(vec![]));
+        }
+
+        // Check all Vjs have the same length
+        let length = vjs.population[0].vj.len();
+        if vjs.population.iter().any(|vj| vj.vj.len() != length) {
+            return Err("All the Vjs of the VjPopulation must have the same length");
+        }
+
+        let mut permus = PermuPopulation::zeros(vjs.size, length+1);
+        for (vj, permu) in vjs.population.iter().zip(permus.population.iter_mut()) {
+            vj.to_permu(permu)?;
+        }
+        Ok(permus)
+    }
+
+    /// Fills the population with samples of a Mallows model under the Cayley distance, centered
+    /// at the `central` permutation with spread parameter `theta`.
+    ///
+    /// The Cayley distance decomposes into `length-1` independent stages: at stage `i` a
+    /// transposition of position `i` with one of the `length-1-i` positions to its right is
+    /// applied with probability `k e^-theta / (1 + k e^-theta)`, where `k = length-1-i`, and
+    /// the number of applied transpositions is the distance to `central`. Thus, the expected
+    /// distance to `central` shrinks as `theta` grows.
+    ///
+    /// # Errors
+    /// Returns an error if the length of `central` does not match the length of the
+    /// `Permutation`s in the population.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    ///
+    /// let central = Permutation::<u8>::random(10);
+    /// let mut pop = PermuPopulation::<u8>::zeros(20, 10);
+    /// pop.sample_mallows_cayley(&central, 100.0, &mut rand::thread_rng()).unwrap();
+    /// pop.population.iter().for_each(|p| assert_eq!(central, *p)); // Huge theta
+    /// ```
+    pub fn sample_mallows_cayley<R: Rng>(&mut self, central: &Permutation<T>, theta: f64,
+                                         rng: &mut R) -> Result<(), &'static str> {
+        let length = central.permu.len();
+        if self.population.iter().any(|p| p.permu.len() != length) {
+            return Err("The length of the central permutation must match the length of the population's permutations");
+        }
+
+        let exp_theta = (-theta).exp();
+        let mut sigma: Vec<usize> = Vec::with_capacity(length);
+
+        self.population.iter_mut().for_each(|permu| {
+            sigma.clear();
+            sigma.extend(0..length);
+
+            (0..length.saturating_sub(1)).for_each(|i| {
+                let k = (length-1-i) as f64;
+                if rng.gen::<f64>() < k * exp_theta / (1.0 + k * exp_theta) {
+                    let j = rng.gen_range(i+1, length);
+                    sigma.swap(i, j);
+                }
+            });
+
+            // Place the sampled permutation around the central one
+            (0..length).for_each(|i| permu.permu[i] = central.permu[sigma[i]]);
+        });
+        Ok(())
+    }
+
+    /// Fills a given `out` population with samples sampled from a given `distr` `Distribution`,
+    /// using the given random number generator. See `Population::sample`.
+    ///
+    /// # Errors
+    /// Returns an error if the size of the `Distribution` does not match the length of the
+    /// `Permutation`s in `out`.
+    ///
+    /// # Example
+    /// ```
+    /// use rand::{SeedableRng, StdRng};
+    /// use permu_rs::permutation::PermuPopulation;
+    /// use permu_rs::Population;
+    ///
+    /// let pop = PermuPopulation::<u8>::random(10, 5);
+    /// let mut samples = PermuPopulation::<u8>::zeros(10, 5);
+    /// let mut rng: StdRng = SeedableRng::from_seed(&[42usize][..]);
+    ///
+    /// PermuPopulation::sample_with_rng(&mut pop.learn(), &mut samples, &mut rng).unwrap();
+    /// samples.population.iter().for_each(|p| assert!(p.is_permu()));
+    /// ```
+    pub fn sample_with_rng<R: Rng>(distr: &mut Distribution, out: &mut PermuPopulation<T>,
+                                   rng: &mut R) -> Result<(), &'static str> {
+        // Check distribution and population's permus' sizes
+        let length = match distr.distribution.len() == out.population[0].permu.len() {
+            true => distr.distribution.len(),
+            false => return Err(SAMPLE_LENGTH_ERR),
+        };
+        
+        distr.soften();
+
+        (0..out.size).for_each(|out_i| {
+            Self::sample_permu(distr, &mut out.population[out_i], length, rng);
+        });
+        Ok(())
+    }
+
+    /// Fills a given `out` population with samples sampled from a given `distr` `Distribution`,
+    /// smoothed with a pseudo-count that grows as the `Distribution` converges. The given
+    /// `Distribution` is not modified, and the default softening of `sample` is not applied.
+    ///
+    /// The pseudo-count added to every element of the counts matrix is computed as follows:
+    ///
+    /// 1. The entropy of the distribution `h`, in `[0, 1]`, is computed with
+    ///    `Distribution::entropy`.
+    /// 2. The pseudo-count is `ceil(base_alpha * (1 - h) * total)`, where `total` is the
+    ///    largest sum of a row (the size of the learned population).
+    ///
+    /// A uniform distribution (`h = 1`) gets no pseudo-count, while a fully converged one
+    /// (`h = 0`) gets `base_alpha` times the population size on every element.
+    ///
+    /// # Errors
+    /// Returns an error if the size of the `Distribution` does not match the length of the
+    /// `Permutation`s in `out`, or if `base_alpha` is negative or not finite.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// use permu_rs::Population;
+    ///
+    /// let distr = PermuPopulation::<u8>::identity(100, 5).learn();
+    /// let mut samples = PermuPopulation::<u8>::zeros(10, 5);
+    ///
+    /// PermuPopulation::sample_adaptive(&distr, &mut samples, 0.1, &mut rand::thread_rng()).unwrap();
+    /// samples.population.iter().for_each(|p| assert!(p.is_permu()));
+    /// ```
+    pub fn sample_adaptive<R: Rng>(distr: &Distribution, out: &mut PermuPopulation<T>,
+                                   base_alpha: f64, rng: &mut R) -> Result<(), &'static str> {
+        let length = distr.distribution.len();
+        if (0..out.size).any(|i| out.population[i].permu.len() != length) {
+            return Err(SAMPLE_LENGTH_ERR);
+        }
+        if !base_alpha.is_finite() || base_alpha < 0.0 {
+            return Err("The base pseudo-count must be finite and non-negative");
+        }
+
+        let entropy = distr.entropy();
+
+        let total = distr.distribution.iter().map(|row| row.iter().sum()).max().unwrap_or(0usize);
+        let alpha = (base_alpha * (1.0 - entropy).max(0.0) * total as f64).ceil() as usize;
+
+        let smoothed = Distribution {
+            distribution : distr.distribution.iter()
+                .map(|row| row.iter().map(|c| c + alpha).collect())
+                .collect(),
+            soften : true,
+        };
+
+        (0..out.size).for_each(|i| {
+            Self::sample_permu(&smoothed, &mut out.population[i], length, rng);
+        });
+        Ok(())
+    }
+
+    /// Fills a given `out` population with samples sampled from a given `distr` `Distribution`,
+    /// using the given random number generator. Unlike `sample`, where the positions of each
+    /// individual are sampled in a fully random order, the positions are partitioned in
+    /// consecutive blocks of `block_size` positions (the last one may be shorter). The order of
+    /// the blocks is random, while the positions inside each block are sampled in increasing
+    /// order. With a `block_size` of 1 this is the same as `sample`, and with a `block_size`
+    /// equal to the length the positions are sampled from first to last.
+    ///
+    /// # Errors
+    /// Returns an error if the size of the `Distribution` does not match the length of the
+    /// `Permutation`s in `out`, or if `block_size` is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// use permu_rs::Population;
+    ///
+    /// let mut distr = PermuPopulation::<u8>::random(10, 6).learn();
+    /// let mut samples = PermuPopulation::<u8>::zeros(10, 6);
+    ///
+    /// PermuPopulation::sample_blocked(&mut distr, &mut samples, 2, &mut rand::thread_rng()).unwrap();
+    /// samples.population.iter().for_each(|p| assert!(p.is_permu()));
+    /// ```
+    pub fn sample_blocked<R: Rng>(distr: &mut Distribution, out: &mut PermuPopulation<T>,
+                                  block_size: usize, rng: &mut R) -> Result<(), &'static str> {
+        let length = distr.distribution.len();
+        if (0..out.size).any(|i| out.population[i].permu.len() != length) {
+            return Err(SAMPLE_LENGTH_ERR);
+        }
+        if block_size == 0 {
+            return Err("The size of the blocks must be greater than 0");
+        }
+
+        distr.soften();
+
+        let blocks: Vec<Vec<usize>> = (0..length).step_by(block_size)
+            .map(|start| (start..std::cmp::min(start + block_size, length)).collect())
+            .collect();
+        let mut block_order: Vec<usize> = (0..blocks.len()).collect();
+
+        (0..out.size).for_each(|i| {
+            rng.shuffle(&mut block_order);
+            let order: Vec<usize> = block_order.iter()
+                .flat_map(|b| blocks[*b].iter().cloned())
+                .collect();
+            Self::sample_permu_in_order(distr, &mut out.population[i], &order, rng);
+        });
+        Ok(())
+    }
+
+    /// Fills each of the given `outs` populations with samples sampled from a given `distr`
+    /// `Distribution`, using the given random number generator. The `Distribution` is soften
+    /// once and shared by all the output populations.
+    ///
+    /// # Errors
+    /// Returns an error if the size of the `Distribution` does not match the length of the
+    /// `Permutation`s of any of the output populations. In that case, no population is sampled.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// use permu_rs::Population;
+    ///
+    /// let mut distr = PermuPopulation::<u8>::random(10, 5).learn();
+    /// let mut island_a = PermuPopulation::<u8>::zeros(10, 5);
+    /// let mut island_b = PermuPopulation::<u8>::zeros(20, 5);
+    ///
+    /// PermuPopulation::sample_batch(&mut distr,
+    ///                               &mut [&mut island_a, &mut island_b],
+    ///                               &mut rand::thread_rng()).unwrap();
+    /// island_a.population.iter().for_each(|p| assert!(p.is_permu()));
+    /// island_b.population.iter().for_each(|p| assert!(p.is_permu()));
+    ///
+    /// let mut wrong = PermuPopulation::<u8>::zeros(10, 4);
+    /// assert!(PermuPopulation::sample_batch(&mut distr,
+    ///                                       &mut [&mut island_a, &mut wrong],
+    ///                                       &mut rand::thread_rng()).is_err());
+    /// ```
+    pub fn sample_batch<R: Rng>(distr: &mut Distribution, outs: &mut [&mut PermuPopulation<T>],
+                                rng: &mut R) -> Result<(), &'static str> {
+        let length = distr.distribution.len();
+        if outs.iter().any(|out| (0..out.size).any(|i| out.population[i].permu.len() != length)) {
+            return Err(SAMPLE_LENGTH_ERR);
+        }
+
+        distr.soften();
+
+        outs.iter_mut().for_each(|out| {
+            (0..out.size).for_each(|i| {
+                Self::sample_permu(distr, &mut out.population[i], length, rng);
+            });
         });
         Ok(())
+    }
+
+    /// Fills the given `Permutation` with a sample from a soften `Distribution`.
+    /// Positions are sampled in random order, and each value is drawn proportionally to its
+    /// count among the values that are not yet used in the permutation.
+    pub(crate) fn sample_permu<R: Rng>(distr: &Distribution, out: &mut Permutation<T>,
+                                       length: usize, rng: &mut R) {
+        let mut order: Vec<usize> = (0..length).collect();
+        rng.shuffle(&mut order);
+        Self::sample_permu_in_order(distr, out, &order, rng);
+    }
+
+    /// Fills the given `Permutation` with a sample from a soften `Distribution`, sampling the
+    /// positions in the given order. See `sample_permu`.
+    fn sample_permu_in_order<R: Rng>(distr: &Distribution, out: &mut Permutation<T>,
+                                     order: &[usize], rng: &mut R) {
+        let mut used_indx = Vec::<usize>::with_capacity(order.len());
+
+        order.iter().for_each(|ord| {
+
+            let (index_f, val_f) : (Vec<usize>, Vec<usize>) = distr.distribution[*ord].iter()
+                .enumerate()
+                .filter(|(index, _)|            // Skip the values already existing in the permutation
+                    !used_indx.contains(index))
+                .unzip();
+
+            let max: usize = val_f.iter().sum();
+
+            let i = match max {
+                // All the remaining values have zero counts, choose one uniformly
+                0 => rng.gen_range(0, index_f.len()),
+                _ => {
+                    let rand: f64 = rng.gen_range(0.0, max as f64);
+                    let mut i = 0;
+                    let mut s = val_f[i];
+                    while (s as f64) < rand {
+                        i += 1;
+                        s += val_f[i];
+                    }
+                    i
+                },
+            };
+            let v = index_f[i];
+            // Never panics, as the boundaries of T are always respected here
+            out.permu[*ord] = match T::try_from(v) {
+                Ok(v) => v,
+                Err(_) => panic!("Conversion error when sampling"),
+            };
+            used_indx.push(index_f[i]);
+        });
+    }
+}
+
+impl<T> Index<usize> for PermuPopulation<T> {
+    type Output = Permutation<T>;
+
+    /// Returns the individual at the given position of the population.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    fn index(&self, i: usize) -> &Permutation<T> {
+        &self.population[i]
+    }
+}
+
+impl<T> IndexMut<usize> for PermuPopulation<T> {
+
+    /// Returns a mutable reference to the individual at the given position of the population.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::permutation::{Permutation, PermuPopulation};
+    /// let mut pop = PermuPopulation::<u8>::identity(3, 4);
+    /// pop[1].permu.swap(0, 1);
+    /// assert_eq!(vec![1,0,2,3], pop[1].permu);
+    /// assert_eq!(Permutation::identity(4), pop[0]);
+    /// ```
+    fn index_mut(&mut self, i: usize) -> &mut Permutation<T> {
+        &mut self.population[i]
+    }
+}
+
+impl<T> Population for PermuPopulation<T> where 
+    T : Copy +
+    From<u8> +
+    TryFrom<usize> +
+    TryInto<usize> +
+    // PartialEq<T> +
+    Eq +
+    rand::distributions::range::SampleRange +
+    std::cmp::PartialOrd +
+    std::ops::Sub +
+    Display + // NOTE : For debugging
+    Debug, // NOTE : For debugging
+{
+    
+    /// Implementation of `learn` method for `PermuPopulation`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::{Population, Distribution};
+    /// use permu_rs::permutation::{PermuPopulation, Permutation};
+    ///
+    /// let v = vec![Permutation::<u8>::from_vec_unsec(vec![0,1,2,3]),
+    ///              Permutation::<u8>::from_vec_unsec(vec![1,2,0,3])];
+    /// let pop = PermuPopulation::from_vec(v); 
+    /// let distr = pop.learn();
+    ///
+    /// let target = vec![vec![1,1,0,0],
+    ///                   vec![0,1,1,0],
+    ///                   vec![1,0,1,0],
+    ///                   vec![0,0,0,2]];
+    /// assert_eq!(target, distr.distribution);
+    /// ```
+    ///
+    // NOTE: (i : positions, j : values)
+    fn learn(&self) -> Distribution { 
+        let m = self.population[0].permu.len(); // Number of positions
+        
+        let mut distr: Vec<Vec<usize>> = vec![vec![0; m]; m]; // Init distribution matrix
+
+        (0..self.size).for_each(|i| {
+            (0..self.population[0].permu.len()).for_each(|j| {
+                let e : usize = match self.population[i].permu[j].try_into() {
+                    Ok(v) => v,
+                    Err(_) => panic!(),
+                }; 
+                distr[j][e] += 1;
+            })
+        });
+        Distribution { distribution : distr , soften : false }
+    }
+
+    /// Implementation of `sample` method for `PermuPopulation`.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use permu_rs::permutation::PermuPopulation;
+    /// use permu_rs::{Population, Distribution};
+    ///
+    /// let pop = PermuPopulation::<u8>::random(1, 5); // Population to learn from
+    /// let mut samples = PermuPopulation::<u8>::zeros(10, 5); // Population to fill with samples
+    /// let mut distr = pop.learn();
+    /// Population::sample(&mut distr, &mut samples).unwrap();
+    /// ```
+    fn sample(distr: &mut Distribution, out: &mut PermuPopulation<T>) -> Result<(), &'static str> {
+        Self::sample_with_rng(distr, out, &mut rand::thread_rng())
     }        
 }
 
 #[cfg(test)]
 mod test_learn {
-    use crate::permutation::PermuPopulation;
+    use rand::{SeedableRng, StdRng};
+    use crate::permutation::{Permutation, PermuPopulation};
     use crate::Population;
 
     #[test]
     fn test() {
         let pop = PermuPopulation::<u8>::random(1, 5);
         pop.population.iter().for_each(|p| println!("{:?}", p.permu));
-        println!("");
+        println!();
 
         let mut samples = PermuPopulation::<u8>::zeros(10, 5);
 
@@ -450,4 +3453,194 @@ mod test_learn {
         Population::sample(&mut distr, &mut samples).unwrap();
         samples.population.iter().for_each(|p| println!("{:?}", p.permu));
     }
+
+    #[test]
+    fn sample_one_concentrates_on_mode() {
+        let pop = PermuPopulation::<u8>::identity(100, 4);
+        let mut distr = pop.learn();
+        let mut rng = rand::thread_rng();
+
+        let mode = Permutation::<u8>::identity(4);
+        let hits = (0..100)
+            .filter(|_| distr.sample_one::<u8, _>(4, &mut rng).unwrap() == mode)
+            .count();
+        assert!(hits > 80);
+
+        assert!(distr.sample_one::<u8, _>(5, &mut rng).is_err());
+    }
+
+    #[test]
+    fn seeded_generation_is_reproducible() {
+        let generation = |seed: usize| {
+            let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
+            let pop = PermuPopulation::<u8>::random_with_rng(20, 10, &mut rng);
+            let mut samples = PermuPopulation::<u8>::zeros(20, 10);
+            PermuPopulation::sample_with_rng(&mut pop.learn(), &mut samples, &mut rng).unwrap();
+            samples
+        };
+        assert_eq!(generation(7), generation(7));
+    }
+
+    #[test]
+    fn blocked_sampling_in_position_order() {
+        // Sampled from first to last position the only possible outcome is the identity
+        let counts = vec![vec![1,0,0], vec![1,1,0], vec![1,1,1]];
+        let mut distr = crate::Distribution::from_counts(counts, true).unwrap();
+        let mut rng: StdRng = SeedableRng::from_seed(&[11usize][..]);
+        let identity = Permutation::<u8>::identity(3);
+
+        let mut samples = PermuPopulation::<u8>::zeros(50, 3);
+        PermuPopulation::sample_blocked(&mut distr, &mut samples, 3, &mut rng).unwrap();
+        assert_eq!(50, samples.count_matching(|p| *p == identity));
+
+        // Single position blocks sample positions in random order
+        PermuPopulation::sample_blocked(&mut distr, &mut samples, 1, &mut rng).unwrap();
+        samples.population.iter().for_each(|p| assert!(p.is_permu()));
+        assert!(samples.count_matching(|p| *p != identity) > 0);
+
+        assert!(PermuPopulation::sample_blocked(&mut distr, &mut samples, 0, &mut rng).is_err());
+
+        // Entries past `size` are neither checked nor sampled
+        samples.population.push(Permutation { permu : vec![9,9] });
+        PermuPopulation::sample_blocked(&mut distr, &mut samples, 2, &mut rng).unwrap();
+        assert_eq!(vec![9,9], samples.population[50].permu);
+
+        let mut wrong = PermuPopulation::<u8>::zeros(5, 4);
+        let err = PermuPopulation::sample_blocked(&mut distr, &mut wrong, 2, &mut rng).unwrap_err();
+        assert!(!err.contains('\n'));
+    }
+
+    #[test]
+    fn batch_sampled_populations_differ() {
+        let mut distr = PermuPopulation::<u8>::random(50, 10).learn();
+        let mut island_a = PermuPopulation::<u8>::zeros(30, 10);
+        let mut island_b = PermuPopulation::<u8>::zeros(30, 10);
+
+        PermuPopulation::sample_batch(&mut distr, &mut [&mut island_a, &mut island_b],
+                                      &mut rand::thread_rng()).unwrap();
+
+        island_a.population.iter().for_each(|p| assert!(p.is_permu()));
+        island_b.population.iter().for_each(|p| assert!(p.is_permu()));
+        assert_ne!(island_a, island_b);
+    }
+
+    #[test]
+    fn batch_sampling_respects_size() {
+        let mut distr = PermuPopulation::<u8>::random(50, 4).learn();
+        let mut island = PermuPopulation::<u8>::zeros(5, 4);
+        island.population.push(Permutation { permu : vec![9,9] });
+
+        // Entries past `size` are neither checked nor sampled
+        PermuPopulation::sample_batch(&mut distr, &mut [&mut island],
+                                      &mut rand::thread_rng()).unwrap();
+        assert!(island.population[..5].iter().all(|p| p.is_permu()));
+        assert_eq!(vec![9,9], island.population[5].permu);
+
+        let mut wrong = PermuPopulation::<u8>::zeros(5, 3);
+        let err = PermuPopulation::sample_batch(&mut distr, &mut [&mut wrong],
+                                                &mut rand::thread_rng()).unwrap_err();
+        assert!(!err.contains('\n'));
+    }
+
+    #[test]
+    fn saved_model_round_trip() {
+        let name = format!("permu_rs_saved_model_round_trip_{}.csv", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        let path = path.to_str().unwrap();
+
+        let pop = PermuPopulation::<u8>::identity(20, 6);
+        pop.save_learned(path).unwrap();
+        assert_eq!(pop.learn().distribution,
+                   crate::Distribution::from_csv(path).unwrap().distribution);
+
+        let mut samples = PermuPopulation::<u8>::zeros(20, 6);
+        PermuPopulation::load_and_sample(path, &mut samples).unwrap();
+        samples.population.iter().for_each(|p| assert!(p.is_permu()));
+
+        let mut wrong = PermuPopulation::<u8>::zeros(20, 5);
+        assert!(PermuPopulation::load_and_sample(path, &mut wrong).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn scale_and_clip_counts() {
+        let pop = PermuPopulation::<u8>::random(30, 6);
+        let normalized = pop.learn_normalized();
+
+        let mut distr = pop.learn();
+        distr.scale(7);
+        distr.distribution.iter().zip(normalized.iter()).for_each(|(row, probs)| {
+            let total: usize = row.iter().sum();
+            row.iter().zip(probs.iter())
+                .for_each(|(c, p)| assert!((*c as f64 / total as f64 - p).abs() < 1e-9));
+        });
+
+        distr.clip(50);
+        assert!(distr.distribution.iter().flatten().all(|c| *c <= 50));
+
+        // Scaling saturates instead of overflowing
+        let mut distr = crate::Distribution { distribution : vec![vec![usize::MAX/2+1, 3]],
+                                              soften : false };
+        distr.scale(2);
+        assert_eq!(vec![usize::MAX, 6], distr.distribution[0]);
+    }
+
+    #[test]
+    fn diversity_through_trait_objects() {
+        let converged_u8 = PermuPopulation::<u8>::identity(20, 6);
+        let random_u16 = PermuPopulation::<u16>::random(200, 6);
+        let pops: Vec<&dyn Population> = vec![&converged_u8, &random_u16];
+
+        let diversities: Vec<f64> = pops.iter().map(|pop| pop.diversity()).collect();
+        assert_eq!(0.0, diversities[0]);
+        assert!(diversities[1] > 0.5 && diversities[1] <= 1.0);
+    }
+
+    #[test]
+    fn diversity_of_converged_populations_is_zero() {
+        // Permutations of length 1 can only be identical
+        let single = PermuPopulation::<u8>::identity(10, 1);
+        assert_eq!(0.0, single.diversity());
+
+        // A converged population must give a positive zero
+        let converged = PermuPopulation::<u8>::identity(10, 5).diversity();
+        assert_eq!(0.0, converged);
+        assert!(converged.is_sign_positive());
+        assert_eq!("0", format!("{}", converged));
+    }
+
+    #[test]
+    fn adaptive_sampling_escapes_converged_distribution() {
+        let probs: Vec<Vec<f64>> = (0..5)
+            .map(|i| (0..5).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+        let mut distr = crate::Distribution::from_probabilities(&probs, 100).unwrap();
+        let mut rng: StdRng = SeedableRng::from_seed(&[3usize][..]);
+        let identity = Permutation::<u8>::identity(5);
+
+        let mut plain = PermuPopulation::<u8>::zeros(50, 5);
+        PermuPopulation::sample_with_rng(&mut distr, &mut plain, &mut rng).unwrap();
+        assert_eq!(50, plain.count_matching(|p| *p == identity));
+
+        let mut adaptive = PermuPopulation::<u8>::zeros(50, 5);
+        PermuPopulation::sample_adaptive(&distr, &mut adaptive, 0.1, &mut rng).unwrap();
+        adaptive.population.iter().for_each(|p| assert!(p.is_permu()));
+        assert!(adaptive.count_matching(|p| *p != identity) > 0);
+
+        // Entries past `size` are neither checked nor sampled
+        adaptive.population.push(Permutation { permu : vec![9,9] });
+        PermuPopulation::sample_adaptive(&distr, &mut adaptive, 0.1, &mut rng).unwrap();
+        assert_eq!(vec![9,9], adaptive.population[50].permu);
+
+        let mut wrong = PermuPopulation::<u8>::zeros(5, 4);
+        let err = PermuPopulation::sample_adaptive(&distr, &mut wrong, 0.1, &mut rng).unwrap_err();
+        assert!(!err.contains('\n'));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn parallel_learn_matches_learn() {
+        let pop = PermuPopulation::<u16>::random(5000, 30);
+        assert_eq!(pop.learn().distribution, pop.learn_par().distribution);
+    }
 }
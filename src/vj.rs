@@ -1,6 +1,7 @@
 use std::convert::{TryFrom, TryInto};
-use rand::Rng;
+use std::fmt;
 use std::fmt::{Debug, Display};
+use rand::Rng;
 
 use crate::permutation;
 
@@ -68,7 +69,7 @@ impl<T> Vj<T> where
     pub fn from_permu(permu: &permutation::Permutation<T>, vj: &mut Vj<T>) -> Result<(), &'static str>{
         
         // Check if sizes are correct
-        if permu.permu.len()-1 != vj.vj.len() {
+        if permu.permu.len() != vj.vj.len()+1 {
             return Err("Lenght of the vj vector must be permu.len()-1");
         }
 
@@ -108,7 +109,7 @@ impl<T> Vj<T> where
     pub fn to_permu(&self, out: &mut permutation::Permutation<T>) -> Result<(), &'static str> {
          
         // Check if sizes are correct
-        if out.permu.len()-1 != self.vj.len() {
+        if out.permu.len() != self.vj.len()+1 {
             return Err("Lenght of the vj vector must be permu.len()-1");
         }
 
@@ -150,6 +151,150 @@ impl<T> Vj<T> where
 
         Ok(())
     } 
+
+    /// Returns the L1 distance between two `Vj` vectors, computed directly in the Vj space.
+    ///
+    /// Note that this distance is not equal to any of the standard distances between the
+    /// `Permutation`s the `Vj`s represent, but it is cheap to compute and it grows as the
+    /// codes move apart.
+    ///
+    /// # Errors
+    /// Returns an error if the lengths of the `Vj`s do not match.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::vj::Vj;
+    /// let a = Vj::<u8>::from_vec(vec![3,0,1,0]);
+    /// let b = Vj::<u8>::from_vec(vec![1,2,1,0]);
+    ///
+    /// assert_eq!(0, a.distance(&a).unwrap());
+    /// assert_eq!(4, a.distance(&b).unwrap());
+    /// assert!(a.distance(&Vj::zeros(3)).is_err());
+    /// ```
+    pub fn distance(&self, other: &Vj<T>) -> Result<usize, &'static str> {
+        if self.vj.len() != other.vj.len() {
+            return Err("The lengths of both Vjs must be equal");
+        }
+
+        let mut distance = 0;
+        for (a, b) in self.vj.iter().zip(other.vj.iter()) {
+            let (a, b): (usize, usize) = match ((*a).try_into(), (*b).try_into()) {
+                (Ok(a), Ok(b)) => (a, b),
+                _ => return Err("Error while converting T to usize"),
+            };
+            distance += a.abs_diff(b);
+        }
+        Ok(distance)
+    }
+
+    /// Checks if every value of the `Vj` is inside its legal range, this is, if the `Vj`
+    /// represents a valid `Permutation`. See `check_vj_matrix`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::vj::Vj;
+    /// assert!(Vj::<u8>::from_vec(vec![3,2,1]).is_valid());
+    /// assert!(!Vj::<u8>::from_vec(vec![0,3,0]).is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        check_vj_matrix(std::slice::from_ref(&self.vj)).is_ok()
+    }
+
+    /// Returns the neighbors of the `Vj` obtained by adding or subtracting one to each of its
+    /// positions. Moves that would take a value out of its legal range are skipped, so every
+    /// neighbor represents a valid `Permutation`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::vj::Vj;
+    /// let vj = Vj::<u8>::from_vec(vec![0,2,0]);
+    /// let neighbors: Vec<Vec<u8>> = vj.increment_neighbors().into_iter().map(|n| n.vj).collect();
+    /// assert_eq!(vec![vec![1,2,0], vec![0,1,0], vec![0,2,1]], neighbors);
+    /// ```
+    pub fn increment_neighbors(&self) -> Vec<Vj<T>> {
+        let length = self.vj.len();
+        let mut neighbors = Vec::with_capacity(2*length);
+
+        (0..length).for_each(|i| {
+            let value: usize = match self.vj[i].try_into() {
+                Ok(v) => v,
+                Err(_) => panic!("Conversion error"),
+            };
+            let max = length - i;
+
+            let candidates = [value.checked_sub(1), Some(value+1)];
+            candidates.iter()
+                .flatten()
+                .filter(|v| **v <= max)
+                .for_each(|v| {
+                    let mut neighbor = self.clone();
+                    neighbor.vj[i] = match T::try_from(*v) {
+                        Ok(v) => v,
+                        Err(_) => panic!("Conversion error"),
+                    };
+                    neighbors.push(neighbor);
+                });
+        });
+        neighbors
+    }
+}
+
+impl<T: Debug> fmt::Display for Vj<T> {
+
+    /// Formats the `Vj` as its inner vector.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::vj::Vj;
+    /// let vj = Vj::<u8>::from_vec(vec![0,2,1]);
+    /// assert_eq!("[0, 2, 1]", format!("{}", vj));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.vj)
+    }
+}
+
+/// Checks that every entry of a matrix of `Vj` vectors (one `Vj` per row) is inside its legal
+/// range, so that each row represents a valid `Permutation`. The value at position `i` of a row
+/// of length `n` must be in `[0, n-i]`.
+///
+/// # Errors
+/// Returns `(row, position, value)` of the first entry that is out of range, in row-major
+/// order.
+///
+/// This is the only public function of the crate whose error is not a `&'static str`. A static
+/// message can not carry the location of the offending entry, which is only known at runtime,
+/// and that location is the whole point of the check when validating external data (for
+/// instance, a matrix read from a file). Callers that need the usual error type can discard
+/// the entry with `map_err`, as shown below.
+///
+/// # Example
+/// ```
+/// use permu_rs::vj::check_vj_matrix;
+/// let valid: Vec<Vec<u8>> = vec![vec![3,2,1], vec![0,0,0]];
+/// assert_eq!(Ok(()), check_vj_matrix(&valid));
+///
+/// // Value 3 at row 1, position 1 is out of range ([0, 2])
+/// let invalid: Vec<Vec<u8>> = vec![vec![3,2,1], vec![0,3,0]];
+/// assert_eq!(Err((1, 1, 3)), check_vj_matrix(&invalid));
+///
+/// // Converted to the error type used by the rest of the crate
+/// let res: Result<(), &'static str> = check_vj_matrix(&invalid)
+///     .map_err(|_| "The matrix contains an invalid Vj");
+/// assert!(res.is_err());
+/// ```
+pub fn check_vj_matrix<T>(matrix: &[Vec<T>]) -> Result<(), (usize, usize, T)> where
+    T : Copy + TryInto<usize>
+{
+    for (row, vj) in matrix.iter().enumerate() {
+        for (position, value) in vj.iter().enumerate() {
+            match (*value).try_into() {
+                Ok(v) if v <= vj.len() - position => (),
+                _ => return Err((row, position, *value)),
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Population of Vj objects. Includes initilializers and transformation tools.
@@ -200,7 +345,64 @@ impl<T> VjPopulation<T> where
         
         VjPopulation { population, size }
     }
+
+    /// Creates a `VjPopulation` of the size given with random `Vj`s of the length specified.
+    /// The value at each position `i` is drawn uniformly from its legal range `[0, length-i]`,
+    /// so every `Vj` in the population represents a valid `Permutation` of length `length+1`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::*;
+    /// let (size, length) = (10, 6);
+    /// let vjs = vj::VjPopulation::<u8>::random_valid(size, length, &mut rand::thread_rng());
+    ///
+    /// let mut permus = permutation::PermuPopulation::<u8>::zeros(size, length+1);
+    /// vjs.to_permus(&mut permus).unwrap();
+    /// permus.population.iter().for_each(|p| assert!(p.is_permu()));
+    /// ```
+    pub fn random_valid<R: Rng>(size: usize, length: usize, rng: &mut R) -> VjPopulation<T> {
+        let mut population: Vec<Vj<T>> = Vec::with_capacity(size);
+
+        (0..size).for_each(|_| {
+            let vj = (0..length).map(|i| match T::try_from(rng.gen_range(0, length-i+1)) {
+                Ok(v) => v,
+                Err(_) => panic!("Conversion error"),
+            }).collect();
+            population.push(Vj::from_vec(vj));
+        });
+
+        VjPopulation { population, size }
+    }
     
+    /// Overwrites every `Vj` of the population in place with a random valid `Vj` of the same
+    /// length, as in `random_valid`. The size of the population does not change.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::vj::VjPopulation;
+    /// let mut vjs = VjPopulation::<u8>::zeros(10, 6);
+    /// vjs.fill_random(&mut rand::thread_rng());
+    /// vjs.population.iter().for_each(|vj| assert!(vj.is_valid()));
+    /// ```
+    pub fn fill_random<R: Rng>(&mut self, rng: &mut R) {
+        self.population.iter_mut().for_each(|vj| {
+            let length = vj.vj.len();
+            vj.vj.iter_mut().enumerate().for_each(|(i, e)| {
+                *e = match T::try_from(rng.gen_range(0, length-i+1)) {
+                    Ok(v) => v,
+                    Err(_) => panic!("Conversion error"),
+                };
+            });
+        });
+    }
+
+    /// Overwrites every `Vj` of the population with 0s, like `VjPopulation::zeros` but without
+    /// allocating.
+    pub fn fill_zeros(&mut self) {
+        let zero = T::from(0u8);
+        self.population.iter_mut().for_each(|vj| vj.vj.iter_mut().for_each(|e| *e = zero));
+    }
+
     /// Transforms the `Vj` to its `Permutation` representation. Fills a given `PermuPopulation`
     /// based on the `Vj`s from the `VjPopulation`. The `Vj` -> `Permutation` transformation is 
     /// done respecting the positions in the population.
@@ -249,13 +451,54 @@ impl<T> VjPopulation<T> where
         });
         Ok(())
     }
+
+    /// Returns a new `PermuPopulation` with the `Permutation` representation of every `Vj`.
+    /// Unlike `to_permus`, the output population is allocated internally with the correct
+    /// size and length. See `PermuPopulation::from_vj`.
+    ///
+    /// # Errors
+    /// Returns an error, before allocating the output, if `size` does not match the number of
+    /// `Vj`s in the population or if the `Vj`s do not all have the same length. Also returns
+    /// an error if any `Vj` can not be converted (see `Vj::to_permu` Errors section).
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::*;
+    /// let (size, length) = (5, 10);
+    /// let vjs = vj::VjPopulation::<u8>::random_valid(size, length-1, &mut rand::thread_rng());
+    ///
+    /// let mut permus = permutation::PermuPopulation::<u8>::zeros(size, length);
+    /// vjs.to_permus(&mut permus).unwrap();
+    ///
+    /// assert_eq!(permus, vjs.to_permus_owned().unwrap());
+    /// ```
+    pub fn to_permus_owned(&self) -> Result<permutation::PermuPopulation<T>, &'static str> {
+        permutation::PermuPopulation::from_vj(self)
+    }
+
+    /// Returns the population as a matrix, where each row is the vector of a `Vj`.
+    ///
+    /// # Example
+    /// ```
+    /// use permu_rs::vj::VjPopulation;
+    /// let vjs = VjPopulation::<u8>::random_valid(10, 4, &mut rand::thread_rng());
+    /// let matrix = vjs.as_matrix();
+    ///
+    /// assert_eq!(vjs.size, matrix.len());
+    /// vjs.population.iter().zip(matrix.iter()).for_each(|(vj, row)| assert_eq!(&vj.vj, row));
+    /// ```
+    pub fn as_matrix(&self) -> Vec<Vec<T>> {
+        self.population.iter().map(|vj| vj.vj.clone()).collect()
+    }
     
     /// Fills an existing `VjPopulation` with `Vj`s based on `Permutations` in a given
     /// `PermuPopulation`. The `Permutation` -> `Vj` transformation is done 
     /// respecting the positions in the population.
     ///
-    /// # Panics 
-    /// The function panics if the internal `Vj::from_permu` returns an `Error`.
+    /// # Errors
+    /// Returns an error if the sizes of both populations are not equal, or the first error
+    /// returned by the internal `Vj::from_permu` (see its Error section). In that case, the
+    /// `Vj`s before the failing individual are already filled.
     ///
     /// # Example
     /// ```
@@ -270,21 +513,94 @@ impl<T> VjPopulation<T> where
     /// let vj_ok = VjPopulation::<u16>::zeros(size, length-1); // Correct result
     /// let permus = PermuPopulation::<u16>::identity(size, length);
     ///
-    /// VjPopulation::from_permus(&permus, &mut vjs);
+    /// VjPopulation::from_permus(&permus, &mut vjs).unwrap();
     /// assert_eq!(vj_ok, vjs);
     /// ```
     ///
     pub fn from_permus(permu_pop: &permutation::PermuPopulation<T>, 
                        vjs: &mut VjPopulation<T>) -> Result<(), &'static str> {
-        
-        permu_pop.population.iter()
-            .enumerate()
-            .for_each(|(i, permu)| { match Vj::from_permu(permu, &mut vjs.population[i]) {
-                Ok(_) => (),
-                Err(e) => panic!(e),
-            }});
+        if permu_pop.population.len() != vjs.population.len() {
+            return Err("The sizes of the populations must be equal");
+        }
 
+        for (permu, vj) in permu_pop.population.iter().zip(vjs.population.iter_mut()) {
+            Vj::from_permu(permu, vj)?;
+        }
         Ok(())
     }
 }
 
+#[cfg(test)]
+mod tests_vj {
+    use crate::permutation::{Permutation, PermuPopulation};
+    use crate::vj::{Vj, VjPopulation, check_vj_matrix};
+
+    #[test]
+    fn to_permus_owned_rejects_ragged_population() {
+        let vjs = VjPopulation::<u8> {
+            population : vec![Vj { vj : vec![0,1] }, Vj { vj : vec![0,1,2] }, Vj { vj : vec![0] }],
+            size : 3,
+        };
+        assert!(vjs.to_permus_owned().is_err());
+
+        let mut truncated = VjPopulation::<u8>::zeros(3, 2);
+        truncated.size = 2;
+        assert!(truncated.to_permus_owned().is_err());
+    }
+
+    #[test]
+    fn increment_neighbors_are_valid() {
+        let vjs = VjPopulation::<u8>::random_valid(20, 7, &mut rand::thread_rng());
+        vjs.population.iter().for_each(|vj| {
+            vj.increment_neighbors().iter().for_each(|n| {
+                assert!(n.is_valid());
+                let mut permu = Permutation::<u8>::identity(8);
+                n.to_permu(&mut permu).unwrap();
+                assert!(permu.is_permu());
+            });
+        });
+
+        // Only one move is possible at each position of a zero Vj
+        assert_eq!(3, Vj::<u8>::zeros(3).increment_neighbors().len());
+    }
+
+    #[test]
+    fn boundary_lengths() {
+        // Length 0 permutations have no Vj representation
+        let empty = Permutation::<u8>::identity(0);
+        assert!(Vj::from_permu(&empty, &mut Vj::zeros(0)).is_err());
+        assert!(Vj::<u8>::zeros(0).to_permu(&mut Permutation::identity(0)).is_err());
+
+        // Length 1 permutations are represented by an empty Vj
+        let single = Permutation::<u8>::identity(1);
+        let mut vj = Vj::<u8>::zeros(0);
+        Vj::from_permu(&single, &mut vj).unwrap();
+        assert!(vj.vj.is_empty());
+
+        let mut permu = Permutation::<u8>::from_vec_unsec(vec![5]);
+        vj.to_permu(&mut permu).unwrap();
+        assert_eq!(single, permu);
+    }
+
+    #[test]
+    fn check_vj_matrix_reports_entry() {
+        let vjs = VjPopulation::<u16>::random_valid(10, 8, &mut rand::thread_rng());
+        assert_eq!(Ok(()), check_vj_matrix(&vjs.as_matrix()));
+
+        let mut matrix = vjs.as_matrix();
+        matrix[6][5] = 4;
+        assert_eq!(Err((6, 5, 4)), check_vj_matrix(&matrix));
+    }
+
+    #[test]
+    fn from_permus_reports_errors() {
+        let mut permus = PermuPopulation::<u8>::random(5, 6);
+        permus.population[3] = Permutation::identity(4);
+
+        let mut vjs = VjPopulation::<u8>::zeros(5, 5);
+        assert!(VjPopulation::from_permus(&permus, &mut vjs).is_err());
+
+        let mut small = VjPopulation::<u8>::zeros(3, 5);
+        assert!(VjPopulation::from_permus(&PermuPopulation::random(5, 6), &mut small).is_err());
+    }
+}